@@ -29,6 +29,9 @@ fn main() -> Result {
     let convert_tuple = File::create(out_dir.join("convert_tuple.rs"))?;
     build_convert_tuple(&convert_tuple)?;
 
+    let characters = File::create(out_dir.join("characters.rs"))?;
+    build_characters(&characters)?;
+
     Ok(())
 }
 
@@ -77,3 +80,25 @@ fn build_convert_tuple(mut out: &File) -> Result {
     }
     Ok(())
 }
+
+/// The characters of field names, each with its code point in binary as a list of `boolean`s
+fn build_characters(mut out: &File) -> Result {
+    let characters = ('a'..='z')
+        .chain('A'..='Z')
+        .map(|c| (c.to_string(), c))
+        .chain(('0'..='9').map(|c| (format!("_{}", c), c)))
+        .chain(Some(("__".to_string(), '_')));
+
+    writeln!(out, "build_character! {{")?;
+    for (name, c) in characters {
+        let code = u32::from(c);
+        let bits = (0..32 - code.leading_zeros())
+            .rev()
+            .map(|i| if code >> i & 1 == 1 { "True" } else { "False" })
+            .collect::<Vec<_>>();
+        writeln!(out, "    {} = [{}]", name, bits.join(" "))?;
+    }
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
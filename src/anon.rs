@@ -1,19 +1,76 @@
-use crate::hlist::{Cons, HList, Nil, Shuffle};
+use core::fmt;
+
+use crate::{
+    boolean::{Bool, Equal, False, True},
+    coprod::{self, CoCons, CoNil},
+    hlist::{self, Cons, HList, Nil},
+};
 
 pub use core::marker::PhantomData;
 
 pub use macros::Transform;
 
 mod deep_transform;
-pub use deep_transform::{DeepTransform, DeepTransformFrom};
+pub use deep_transform::{
+    ConvertLeaves, DeepConvert, DeepTransform, DeepTransformFrom, DeepView, KeepLeaves, TryDeepTransform,
+    TryDeepTransformFrom,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+// `Named` is deliberately not `Debug`, so that anon records can be printed as `{ x: 1.0 }`
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Named<T, Name: 'static>(pub T, PhantomData<Name>);
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Unnamed<T>(pub T);
 
+impl<T: fmt::Debug> fmt::Debug for Unnamed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { self.0.fmt(f) }
+}
+
+/// Named fields are keyed by their name, records can mix them with unnamed fields, `{ x: 1, 1: 2 }`
+///
+/// Name types that aren't made with [`field!`](crate::field) print once they implement [`FieldName`].
+impl<T: fmt::Debug, Name: FieldName, R: hlist::DebugElements> hlist::DebugElements for Cons<Named<T, Name>, R> {
+    const NAMED: bool = true;
+
+    fn debug_elements(
+        &self,
+        position: usize,
+        entry: &mut dyn FnMut(&dyn fmt::Debug, &dyn fmt::Debug) -> fmt::Result,
+    ) -> fmt::Result {
+        entry(&self.value.name(), &self.value.0)?;
+        self.rest.debug_elements(position + 1, entry)
+    }
+}
+
+/// Prints a named variant like a tuple variant, `Value(Moved({ x: 1.0 }))`
+///
+/// `DebugTuple::field` is called by path, since `Access::field` is in scope here.
+impl<T: fmt::Debug, Name: FieldName, R: fmt::Debug> fmt::Debug for CoCons<Named<T, Name>, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Variant<'a, T, Name: 'static>(&'a Named<T, Name>);
+
+        impl<T: fmt::Debug, Name: FieldName> fmt::Debug for Variant<'_, T, Name> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::DebugTuple::field(&mut f.debug_tuple(Name::NAME), &self.0 .0).finish()
+            }
+        }
+
+        match self {
+            CoCons::Value(value) => fmt::DebugTuple::field(&mut f.debug_tuple("Value"), &Variant(value)).finish(),
+            CoCons::Rest(rest) => fmt::DebugTuple::field(&mut f.debug_tuple("Rest"), rest).finish(),
+        }
+    }
+}
+
 impl<T, Name: 'static> Named<T, Name> {
     pub const fn new(value: T) -> Self { Self(value, PhantomData) }
+
+    pub fn name(&self) -> DisplayName<Name>
+    where
+        Name: FieldName,
+    {
+        DisplayName(PhantomData)
+    }
 }
 
 #[macro_export]
@@ -23,6 +80,50 @@ macro_rules! field {
     };
 }
 
+/// Borrows a field of an anon record, `get!(record.x)`, or of a nested anon record, `get!(record.x.y)`
+///
+/// The record may also be a reference, like with struct fields.
+///
+/// ```
+/// use typsy::{anon, get, Anon};
+///
+/// let record = anon!(x = 1, inner = anon!(y = "y"));
+/// assert_eq!(*get!(record.x), 1);
+/// assert_eq!(*get!(record.inner.y), "y");
+///
+/// fn x(record: &Anon!(x: u8)) -> &u8 { get!(record.x) }
+/// assert_eq!(*x(&anon!(x = 2)), 2);
+/// ```
+#[macro_export]
+macro_rules! get {
+    ($record:ident $(. $field:ident)+) => { $crate::get!(@access ($record) $($field)+) };
+    (($record:expr) $(. $field:ident)+) => { $crate::get!(@access ($record) $($field)+) };
+    (@access ($record:expr) $field:ident $($rest:ident)*) => {
+        $crate::get!(@access ($record.get_field_by_name::<$crate::field!($field)>()) $($rest)*)
+    };
+    (@access ($record:expr)) => { $record };
+}
+
+/// Mutably borrows a field of an anon record, `get_mut!(record.x)`, see `get!`
+///
+/// ```
+/// use typsy::{anon, get_mut};
+///
+/// let mut record = anon!(x = 1, inner = anon!(y = "y"));
+/// *get_mut!(record.x) += 1;
+/// *get_mut!(record.inner.y) = "z";
+/// assert_eq!(record, anon!(x = 2, inner = anon!(y = "z")));
+/// ```
+#[macro_export]
+macro_rules! get_mut {
+    ($record:ident $(. $field:ident)+) => { $crate::get_mut!(@access ($record) $($field)+) };
+    (($record:expr) $(. $field:ident)+) => { $crate::get_mut!(@access ($record) $($field)+) };
+    (@access ($record:expr) $field:ident $($rest:ident)*) => {
+        $crate::get_mut!(@access ($record.get_field_mut_by_name::<$crate::field!($field)>()) $($rest)*)
+    };
+    (@access ($record:expr)) => { $record };
+}
+
 #[macro_export]
 macro_rules! Anon {
     (
@@ -51,10 +152,25 @@ macro_rules! anon {
     };
 }
 
-pub trait AnonType: HList {}
+pub trait AnonType {}
 impl AnonType for Nil {}
-impl<T, R: AnonType, Name> AnonType for Cons<Named<T, Name>, R> {}
-impl<T, R: AnonType> AnonType for Cons<Unnamed<T>, R> {}
+impl<T, R: AnonType + HList, Name> AnonType for Cons<Named<T, Name>, R> {}
+impl<T, R: AnonType + HList> AnonType for Cons<Unnamed<T>, R> {}
+impl AnonType for CoNil {}
+impl<T, R: AnonType + coprod::CoProd, Name> AnonType for CoCons<Named<T, Name>, R> {}
+
+/// Reorders an anon record or an anon coproduct, see `hlist::Shuffle` and `coprod::Shuffle`
+pub trait Reorder<T, N> {
+    fn reorder(self) -> T;
+}
+
+impl<T: hlist::Shuffle<U, N>, U: HList, N> Reorder<U, (Nil, N)> for T {
+    fn reorder(self) -> U { self.shuffle() }
+}
+
+impl<T: coprod::Shuffle<U, N>, U: coprod::CoProd, N> Reorder<U, (CoNil, N)> for T {
+    fn reorder(self) -> U { self.shuffle() }
+}
 
 impl<T> Access for T {}
 pub trait Access {
@@ -79,6 +195,155 @@ pub trait Access {
         let (field, rest) = self.take();
         (field.0, rest)
     }
+
+    /// Adds the field `Name`, which must not already be in `self`
+    fn insert_field<Name, T>(self, value: T) -> Cons<Named<T, Name>, Self>
+    where
+        Self: Lacks<Name> + HList + Sized,
+    {
+        Cons {
+            value: Named::new(value),
+            rest: self,
+        }
+    }
+
+    /// Replaces the value of the field `Name` with `value`, which may have a different type,
+    /// and returns the old value along with the new record
+    fn replace_field<Name, T>(self, value: T) -> (Self::Value, Self::Output)
+    where
+        Self: ReplaceField<Name, T> + Sized,
+    {
+        self.replace(value)
+    }
+}
+
+/// Looks up the field `Name` of an anon record by comparing names, see `get!` and `get_mut!`
+pub trait GetField<Name> {
+    type Value;
+
+    fn get_field(&self) -> &Self::Value;
+
+    fn get_field_mut(&mut self) -> &mut Self::Value;
+}
+
+impl<U: IsField<Name>, R, Name> GetField<Name> for Cons<U, R>
+where
+    Self: GetFieldAt<Name, U::Output>,
+{
+    type Value = <Self as GetFieldAt<Name, U::Output>>::Value;
+
+    fn get_field(&self) -> &Self::Value { self.get_field_at() }
+
+    fn get_field_mut(&mut self) -> &mut Self::Value { self.get_field_at_mut() }
+}
+
+// `get!` and `get_mut!` call these with method syntax, so that borrowed records are dereferenced
+#[doc(hidden)]
+impl<T, R> Cons<T, R> {
+    pub fn get_field_by_name<Name>(&self) -> &<Self as GetField<Name>>::Value
+    where
+        Self: GetField<Name>,
+    {
+        self.get_field()
+    }
+
+    pub fn get_field_mut_by_name<Name>(&mut self) -> &mut <Self as GetField<Name>>::Value
+    where
+        Self: GetField<Name>,
+    {
+        self.get_field_mut()
+    }
+}
+
+/// `GetField`, where `B` is whether the first field is `Name`
+#[doc(hidden)]
+pub trait GetFieldAt<Name, B> {
+    type Value;
+
+    fn get_field_at(&self) -> &Self::Value;
+
+    fn get_field_at_mut(&mut self) -> &mut Self::Value;
+}
+
+impl<T, R, Name> GetFieldAt<Name, True> for Cons<Named<T, Name>, R> {
+    type Value = T;
+
+    fn get_field_at(&self) -> &T { &self.value.0 }
+
+    fn get_field_at_mut(&mut self) -> &mut T { &mut self.value.0 }
+}
+
+impl<U, R: GetField<Name>, Name> GetFieldAt<Name, False> for Cons<U, R> {
+    type Value = R::Value;
+
+    fn get_field_at(&self) -> &R::Value { self.rest.get_field() }
+
+    fn get_field_at_mut(&mut self) -> &mut R::Value { self.rest.get_field_mut() }
+}
+
+/// An anon record that doesn't have the field `Name`
+///
+/// ```compile_fail
+/// use typsy::{anon, anon::Access, field};
+///
+/// anon!(x = 1).insert_field::<field!(x), _>(2);
+/// ```
+pub trait Lacks<Name> {}
+
+impl<Name> Lacks<Name> for Nil {}
+
+impl<U: IsField<Name, Output = False>, R: Lacks<Name>, Name> Lacks<Name> for Cons<U, R> {}
+
+/// Replaces the value of the field `Name` with a `T`
+pub trait ReplaceField<Name, T> {
+    type Value;
+    type Output;
+
+    fn replace(self, value: T) -> (Self::Value, Self::Output);
+}
+
+impl<U: IsField<Name>, R, Name, T> ReplaceField<Name, T> for Cons<U, R>
+where
+    Self: ReplaceFieldAt<Name, T, U::Output>,
+{
+    type Value = <Self as ReplaceFieldAt<Name, T, U::Output>>::Value;
+    type Output = <Self as ReplaceFieldAt<Name, T, U::Output>>::Output;
+
+    fn replace(self, value: T) -> (Self::Value, Self::Output) { self.replace_at(value) }
+}
+
+/// `ReplaceField`, where `B` is whether the first field is `Name`
+#[doc(hidden)]
+pub trait ReplaceFieldAt<Name, T, B> {
+    type Value;
+    type Output;
+
+    fn replace_at(self, value: T) -> (Self::Value, Self::Output);
+}
+
+impl<U, R, Name, T> ReplaceFieldAt<Name, T, True> for Cons<Named<U, Name>, R> {
+    type Value = U;
+    type Output = Cons<Named<T, Name>, R>;
+
+    fn replace_at(self, value: T) -> (U, Self::Output) {
+        (self.value.0, Cons {
+            value: Named::new(value),
+            rest: self.rest,
+        })
+    }
+}
+
+impl<U, R: ReplaceField<Name, T>, Name, T> ReplaceFieldAt<Name, T, False> for Cons<U, R> {
+    type Value = R::Value;
+    type Output = Cons<U, R::Output>;
+
+    fn replace_at(self, value: T) -> (Self::Value, Self::Output) {
+        let (old, rest) = self.rest.replace(value);
+        (old, Cons {
+            value: self.value,
+            rest,
+        })
+    }
 }
 
 pub trait RemoveField<N, I> {
@@ -95,6 +360,14 @@ impl<T, Name, R> RemoveField<Name, crate::peano::Zero> for Cons<Named<T, Name>,
     fn remove_field(self) -> (Self::Value, Self::Remainder) { (self.value.0, self.rest) }
 }
 
+// `as_ref::AsRef` borrows an anon record as a list of references to its fields
+impl<'a, T, Name, R> RemoveField<Name, crate::peano::Zero> for Cons<&'a Named<T, Name>, R> {
+    type Value = &'a T;
+    type Remainder = R;
+
+    fn remove_field(self) -> (Self::Value, Self::Remainder) { (&self.value.0, self.rest) }
+}
+
 impl<T, Name, R: RemoveField<Name, N>, N> RemoveField<Name, crate::peano::Succ<N>> for Cons<T, R> {
     type Value = R::Value;
     type Remainder = Cons<T, R::Remainder>;
@@ -108,6 +381,204 @@ impl<T, Name, R: RemoveField<Name, N>, N> RemoveField<Name, crate::peano::Succ<N
     }
 }
 
+/// Whether an element of an anon record is the field `Name`
+pub trait IsField<Name> {
+    type Output: Bool;
+}
+
+impl<T, Name, Other: Equal<Name>> IsField<Name> for Named<T, Other> {
+    type Output = Other::Output;
+}
+
+impl<T, Name> IsField<Name> for Unnamed<T> {
+    type Output = False;
+}
+
+/// Removes the field `Name` from an anon record, or makes a `T` with `Default` if there is no such field
+pub trait RemoveFieldOrDefault<Name, T> {
+    type Remainder;
+
+    fn remove_field_or_default(self) -> (T, Self::Remainder);
+}
+
+impl<Name, T: Default> RemoveFieldOrDefault<Name, T> for Nil {
+    type Remainder = Nil;
+
+    fn remove_field_or_default(self) -> (T, Nil) { (T::default(), Nil) }
+}
+
+impl<U: IsField<Name>, R, Name, T> RemoveFieldOrDefault<Name, T> for Cons<U, R>
+where
+    Self: RemoveFieldOrDefaultAt<Name, T, U::Output>,
+{
+    type Remainder = <Self as RemoveFieldOrDefaultAt<Name, T, U::Output>>::Remainder;
+
+    fn remove_field_or_default(self) -> (T, Self::Remainder) { self.remove_field_or_default_at() }
+}
+
+/// `RemoveFieldOrDefault`, where `B` is whether the first field is `Name`
+#[doc(hidden)]
+pub trait RemoveFieldOrDefaultAt<Name, T, B> {
+    type Remainder;
+
+    fn remove_field_or_default_at(self) -> (T, Self::Remainder);
+}
+
+impl<T, Name, R> RemoveFieldOrDefaultAt<Name, T, True> for Cons<Named<T, Name>, R> {
+    type Remainder = R;
+
+    fn remove_field_or_default_at(self) -> (T, R) { (self.value.0, self.rest) }
+}
+
+impl<U, R: RemoveFieldOrDefault<Name, T>, Name, T> RemoveFieldOrDefaultAt<Name, T, False> for Cons<U, R> {
+    type Remainder = Cons<U, R::Remainder>;
+
+    fn remove_field_or_default_at(self) -> (T, Self::Remainder) {
+        let (value, rest) = self.rest.remove_field_or_default();
+        (value, Cons {
+            value: self.value,
+            rest,
+        })
+    }
+}
+
+/// Builds the anon record `T` from the fields of `Self`, filling fields that `Self` lacks with `Default`
+pub trait FillDefaults<T> {
+    type Remainder;
+
+    fn fill_defaults(self) -> (T, Self::Remainder);
+}
+
+impl<L> FillDefaults<Nil> for L {
+    type Remainder = L;
+
+    fn fill_defaults(self) -> (Nil, L) { (Nil, self) }
+}
+
+impl<L: RemoveFieldOrDefault<Name, T>, T, Name, R> FillDefaults<Cons<Named<T, Name>, R>> for L
+where
+    L::Remainder: FillDefaults<R>,
+{
+    type Remainder = <L::Remainder as FillDefaults<R>>::Remainder;
+
+    fn fill_defaults(self) -> (Cons<Named<T, Name>, R>, Self::Remainder) {
+        let (value, rest) = self.remove_field_or_default();
+        let (rest, remainder) = rest.fill_defaults();
+        (
+            Cons {
+                value: Named::new(value),
+                rest,
+            },
+            remainder,
+        )
+    }
+}
+
+/// Removes the field `Name` from an anon record, if it has one
+pub trait RemoveFieldIfPresent<Name> {
+    type Remainder;
+
+    fn remove_field_if_present(self) -> Self::Remainder;
+}
+
+impl<Name> RemoveFieldIfPresent<Name> for Nil {
+    type Remainder = Nil;
+
+    fn remove_field_if_present(self) -> Nil { Nil }
+}
+
+impl<U: IsField<Name>, R, Name> RemoveFieldIfPresent<Name> for Cons<U, R>
+where
+    Self: RemoveFieldIfPresentAt<Name, U::Output>,
+{
+    type Remainder = <Self as RemoveFieldIfPresentAt<Name, U::Output>>::Remainder;
+
+    fn remove_field_if_present(self) -> Self::Remainder { self.remove_field_if_present_at() }
+}
+
+/// `RemoveFieldIfPresent`, where `B` is whether the first field is `Name`
+#[doc(hidden)]
+pub trait RemoveFieldIfPresentAt<Name, B> {
+    type Remainder;
+
+    fn remove_field_if_present_at(self) -> Self::Remainder;
+}
+
+impl<U, R, Name> RemoveFieldIfPresentAt<Name, True> for Cons<U, R> {
+    type Remainder = R;
+
+    fn remove_field_if_present_at(self) -> R { self.rest }
+}
+
+impl<U, R: RemoveFieldIfPresent<Name>, Name> RemoveFieldIfPresentAt<Name, False> for Cons<U, R> {
+    type Remainder = Cons<U, R::Remainder>;
+
+    fn remove_field_if_present_at(self) -> Self::Remainder {
+        Cons {
+            value: self.value,
+            rest: self.rest.remove_field_if_present(),
+        }
+    }
+}
+
+/// Removes every field of the anon record `T` from `Self`
+pub trait RemoveFields<T> {
+    type Remainder;
+
+    fn remove_fields(self) -> Self::Remainder;
+}
+
+impl<L> RemoveFields<Nil> for L {
+    type Remainder = L;
+
+    fn remove_fields(self) -> L { self }
+}
+
+impl<L: RemoveFieldIfPresent<Name>, T, Name, R> RemoveFields<Cons<Named<T, Name>, R>> for L
+where
+    L::Remainder: RemoveFields<R>,
+{
+    type Remainder = <L::Remainder as RemoveFields<R>>::Remainder;
+
+    fn remove_fields(self) -> Self::Remainder { self.remove_field_if_present().remove_fields() }
+}
+
+/// Merges two anon records, fields of `T` override fields of `Self` with the same name
+///
+/// The fields of `Self` that are kept come first, followed by all the fields of `T`.
+pub trait Merge<T> {
+    type Output;
+
+    fn merge(self, other: T) -> Self::Output;
+}
+
+impl<L: RemoveFields<T>, T: HList> Merge<T> for L
+where
+    L::Remainder: hlist::Concat<T>,
+{
+    type Output = <L::Remainder as hlist::Concat<T>>::Output;
+
+    fn merge(self, other: T) -> Self::Output { hlist::Concat::concat(self.remove_fields(), other) }
+}
+
+pub trait InjectField<N, I> {
+    type Value;
+
+    fn inject_field(value: Self::Value) -> Self;
+}
+
+impl<T, Name, R> InjectField<Name, crate::peano::Zero> for CoCons<Named<T, Name>, R> {
+    type Value = T;
+
+    fn inject_field(value: T) -> Self { CoCons::Value(Named::new(value)) }
+}
+
+impl<T, Name, R: InjectField<Name, N>, N> InjectField<Name, crate::peano::Succ<N>> for CoCons<T, R> {
+    type Value = R::Value;
+
+    fn inject_field(value: R::Value) -> Self { CoCons::Rest(R::inject_field(value)) }
+}
+
 pub trait Transform: Sized {
     type Canon: AnonType;
 
@@ -117,9 +588,9 @@ pub trait Transform: Sized {
 
     fn transform<O: Transform, N>(self) -> O
     where
-        Self::Canon: Shuffle<O::Canon, N>,
+        Self::Canon: Reorder<O::Canon, N>,
     {
-        O::from_canon(self.into_canon().shuffle())
+        O::from_canon(self.into_canon().reorder())
     }
 
     fn deep_transform<O: Transform, N>(self) -> O
@@ -128,6 +599,87 @@ pub trait Transform: Sized {
     {
         O::from_canon(self.into_canon().deep_transform())
     }
+
+    /// Like `transform`, but fields of `O` that are missing from `self` are filled with `Default`
+    fn transform_with_defaults<O: Transform>(self) -> O
+    where
+        Self::Canon: FillDefaults<O::Canon, Remainder = Nil>,
+    {
+        let (canon, Nil) = self.into_canon().fill_defaults();
+        O::from_canon(canon)
+    }
+
+    /// Like `transform`, but fields of `self` that are missing from `O` are dropped
+    fn transform_lossy<O: Transform, N>(self) -> O
+    where
+        Self::Canon: hlist::Split<O::Canon, N>,
+        O::Canon: HList,
+    {
+        let (canon, _) = hlist::Split::split(self.into_canon());
+        O::from_canon(canon)
+    }
+
+    /// Overrides the fields of `self` with the fields of the anon record `patch`
+    fn update<P, N>(self, patch: P) -> Self
+    where
+        Self::Canon: Merge<P>,
+        <Self::Canon as Merge<P>>::Output: Reorder<Self::Canon, N>,
+    {
+        Self::from_canon(self.into_canon().merge(patch).reorder())
+    }
+
+    /// Like `deep_transform`, but leaves convert with `From`, see `ConvertLeaves`
+    fn deep_convert<O: Transform, N>(self) -> O
+    where
+        Self::Canon: DeepConvert<O::Canon, N>,
+    {
+        O::from_canon(self.into_canon().deep_convert())
+    }
+
+    fn try_transform<O: TryTransform, N>(self) -> Result<O, O::Error>
+    where
+        Self::Canon: Reorder<O::Canon, N>,
+    {
+        O::try_from_canon(self.into_canon().reorder())
+    }
+
+    fn try_deep_transform<O: TryDeepTransformFrom<Self::Canon, N>, N>(self) -> Result<O, O::Error> {
+        O::try_deep_transform_from(self.into_canon())
+    }
+}
+
+/// Builds `Self` from its canon, but may fail, for example because it validates its fields
+///
+/// Every `Transform` is a `TryTransform` that never fails. With `#[typsy(validate = "path", error = "Type")]`,
+/// `#[derive(Transform)]` implements only `TryTransform`, so the type can't be built without `validate`.
+///
+/// ```compile_fail
+/// use typsy::{anon, anon::{IntoNamed, Transform}};
+///
+/// #[derive(Transform)]
+/// #[typsy(validate = "Port::validate", error = "u16")]
+/// struct Port {
+///     pub number: u16,
+/// }
+///
+/// impl Port {
+///     fn validate(self) -> Result<Self, u16> { if self.number != 0 { Ok(self) } else { Err(self.number) } }
+/// }
+///
+/// anon!(number = 0u16).into_named::<Port, _>();
+/// ```
+pub trait TryTransform: Sized {
+    type Canon: AnonType;
+    type Error;
+
+    fn try_from_canon(anon: Self::Canon) -> Result<Self, Self::Error>;
+}
+
+impl<T: Transform> TryTransform for T {
+    type Canon = T::Canon;
+    type Error = core::convert::Infallible;
+
+    fn try_from_canon(anon: Self::Canon) -> Result<Self, Self::Error> { Ok(T::from_canon(anon)) }
 }
 
 impl Transform for Nil {
@@ -149,17 +701,127 @@ where
     fn into_canon(self) -> Self::Canon { self }
 }
 
+impl Transform for CoNil {
+    type Canon = Self;
+
+    fn from_canon(anon: Self::Canon) -> Self { anon }
+
+    fn into_canon(self) -> Self::Canon { self }
+}
+
+impl<T, R> Transform for CoCons<T, R>
+where
+    Self: AnonType,
+{
+    type Canon = Self;
+
+    fn from_canon(anon: Self::Canon) -> Self { anon }
+
+    fn into_canon(self) -> Self::Canon { self }
+}
+
 impl<T: AnonType> IntoNamed for T {}
 pub trait IntoNamed: Sized + AnonType {
     fn into_named<T, N>(self) -> T
     where
-        Self: Shuffle<T::Canon, N>,
+        Self: Reorder<T::Canon, N>,
         T: Transform,
     {
-        T::from_canon(self.shuffle())
+        T::from_canon(self.reorder())
+    }
+}
+
+/// A type-level field name, as produced by [`field!`](crate::field)
+pub trait FieldName {
+    /// the name, built at compile time so that it can be borrowed for `'static`
+    const NAME: &'static str;
+
+    fn write_name<W: ?Sized + fmt::Write>(f: &mut W) -> fmt::Result { f.write_str(Self::NAME) }
+
+    fn matches(name: &str) -> bool { name == Self::NAME }
+}
+
+impl FieldName for Nil {
+    const NAME: &'static str = "";
+}
+
+impl<A, B, C, D, R: FieldName> FieldName for Cons<character::Chunk<A, B, C, D>, R>
+where
+    A: character::Slot,
+    B: character::Slot,
+    C: character::Slot,
+    D: character::Slot,
+{
+    const NAME: &'static str = NameBuf::EMPTY
+        .push(A::CHAR)
+        .push(B::CHAR)
+        .push(C::CHAR)
+        .push(D::CHAR)
+        .push_str(R::NAME)
+        .as_str();
+}
+
+/// Builds `FieldName::NAME` during const evaluation
+#[derive(Clone, Copy)]
+struct NameBuf {
+    bytes: [u8; NameBuf::CAPACITY],
+    len: usize,
+}
+
+impl NameBuf {
+    const CAPACITY: usize = 256;
+    const EMPTY: Self = Self {
+        bytes: [0; Self::CAPACITY],
+        len: 0,
+    };
+
+    const fn push(self, c: Option<char>) -> Self {
+        match c {
+            Some(c) => self.push_str(c.encode_utf8(&mut [0; 4])),
+            None => self,
+        }
+    }
+
+    const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        assert!(self.len + bytes.len() <= Self::CAPACITY, "field names are limited to 256 bytes");
+
+        let mut i = 0;
+        while i < bytes.len() {
+            self.bytes[self.len + i] = bytes[i];
+            i += 1;
+        }
+        self.len += bytes.len();
+        self
+    }
+
+    const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.bytes.split_at(self.len).0) {
+            Ok(name) => name,
+            Err(_) => panic!("field names are valid utf-8"),
+        }
     }
 }
 
+#[derive(Clone, Copy)]
+pub struct DisplayName<Name>(PhantomData<Name>);
+
+impl<Name: FieldName> DisplayName<Name> {
+    pub const fn new() -> Self { Self(PhantomData) }
+}
+
+impl<Name: FieldName> Default for DisplayName<Name> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<Name: FieldName> fmt::Display for DisplayName<Name> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { Name::write_name(f) }
+}
+
+impl<Name: FieldName> fmt::Debug for DisplayName<Name> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(self, f) }
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! character {
@@ -170,16 +832,147 @@ macro_rules! character {
 
 #[allow(non_camel_case_types)]
 pub mod character {
+    use core::marker::PhantomData;
+
+    use crate::{
+        boolean::{And, Bool, Equal, False, True},
+        hlist::{Cons, Nil},
+    };
+
+    pub trait Character {
+        const CHAR: char;
+
+        /// the code point of `CHAR` in binary, used to compare names with `boolean::Equal`
+        type Code;
+    }
+
+    /// A character without a name in this module, encoded by its code point in binary
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Unicode<Code>(PhantomData<Code>);
+
+    /// A code point in binary, as a list of `boolean`s
+    pub trait CodePoint {
+        const LEN: u32;
+        const VALUE: u32;
+    }
+
+    impl CodePoint for Nil {
+        const LEN: u32 = 0;
+        const VALUE: u32 = 0;
+    }
+
+    impl<B: Bool, R: CodePoint> CodePoint for Cons<B, R> {
+        const LEN: u32 = R::LEN + 1;
+        const VALUE: u32 = (B::VALUE as u32) << R::LEN | R::VALUE;
+    }
+
+    impl<Code: CodePoint> Character for Unicode<Code> {
+        const CHAR: char = match char::from_u32(Code::VALUE) {
+            Some(c) => c,
+            None => panic!("invalid code point"),
+        };
+
+        type Code = Code;
+    }
+
+    /// Up to four characters of a name, the unused ones are `Pad`
+    ///
+    /// Names are lists of chunks rather than lists of characters, which keeps their types shallow.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Chunk<A, B = Pad, C = Pad, D = Pad>(PhantomData<(A, B, C, D)>);
+
+    /// Fills the unused slots of the last `Chunk` of a name
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum Pad {}
+
+    /// A slot of a `Chunk`, either a `Character` or `Pad`
+    pub trait Slot {
+        const CHAR: Option<char>;
+    }
+
+    impl<C: Character> Slot for C {
+        const CHAR: Option<char> = Some(C::CHAR);
+    }
+
+    impl Slot for Pad {
+        const CHAR: Option<char> = None;
+    }
+
+    impl Equal<Pad> for Pad {
+        type Output = True;
+    }
+
+    impl<C: Character> Equal<C> for Pad {
+        type Output = False;
+    }
+
+    impl<C: Character> Equal<Pad> for C {
+        type Output = False;
+    }
+
+    impl<A, B, C, D, E, F, G, H> Equal<Chunk<E, F, G, H>> for Chunk<A, B, C, D>
+    where
+        A: Equal<E>,
+        B: Equal<F>,
+        C: Equal<G>,
+        D: Equal<H>,
+        C::Output: And<D::Output>,
+        B::Output: And<<C::Output as And<D::Output>>::Output>,
+        A::Output: And<<B::Output as And<<C::Output as And<D::Output>>::Output>>::Output>,
+    {
+        type Output = <A::Output as And<<B::Output as And<<C::Output as And<D::Output>>::Output>>::Output>>::Output;
+    }
+
+    impl<C: Character, D: Character> Equal<D> for C
+    where
+        C::Code: Equal<D::Code>,
+    {
+        type Output = <C::Code as Equal<D::Code>>::Output;
+    }
+
+    // a digit is a name on its own, see `digit_name`, which differs from every other name
+    impl<C: Character> Equal<Nil> for C {
+        type Output = False;
+    }
+
+    impl<C: Character, D, S> Equal<Cons<D, S>> for C {
+        type Output = False;
+    }
+
+    impl<D: Character> Equal<D> for Nil {
+        type Output = False;
+    }
+
+    impl<T, R, D: Character> Equal<D> for Cons<T, R> {
+        type Output = False;
+    }
+
     macro_rules! build_character {
-        ($($c:ident)*) => {$(
+        ($($c:ident = [$($bit:ident)*])*) => {$(
             #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
             pub enum $c {}
+
+            impl Character for $c {
+                const CHAR: char = {
+                    let name = stringify!($c).as_bytes();
+                    name[name.len() - 1] as char
+                };
+
+                type Code = crate::HList!($(crate::boolean::$bit),*);
+            }
         )*};
     }
 
-    build_character! {
-        a b c d e f g h i j k l m n o p q r s t u v w x y z
-        A B C D E F G H I J K L M N O P Q R S T U V W X Y Z
-        _0 _1 _2 _3 _4 _5 _6 _7 _8 _9 __
+    include!(concat!(env!("OUT_DIR"), "/characters.rs"));
+
+    // `field!(_0)` names a digit directly, see `typsy_macros::name`
+    macro_rules! digit_name {
+        ($($digit:ident)*) => {$(
+            impl super::FieldName for $digit {
+                const NAME: &'static str = stringify!($digit);
+            }
+        )*};
     }
+
+    digit_name! { _0 _1 _2 _3 _4 _5 _6 _7 _8 _9 }
 }
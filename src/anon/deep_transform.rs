@@ -1,12 +1,28 @@
-use std::vec::Vec;
+use core::convert::Infallible;
 
-use super::{Named, Unnamed};
-use crate::hlist::*;
+use super::{InjectField, Named, Unnamed};
+use crate::{
+    coprod::{self, CoCons, CoNil},
+    hlist::*,
+};
 
-pub trait DeepTransformFrom<T, I> {
+/// Transforms `T` into `Self` structurally, down to the leaves, which `M` says how to transform
+///
+/// `M` is either `KeepLeaves`, for `DeepTransform`, or `ConvertLeaves`, for `DeepConvert`.
+pub trait DeepTransformFrom<T, I, M = KeepLeaves> {
     fn deep_transform_from(value: T) -> Self;
 }
 
+/// The leaf mode of `DeepTransform`, where leaves stay the same type
+pub struct KeepLeaves;
+
+/// The leaf mode of `DeepConvert`, where leaves convert with `From`
+///
+/// Leaves are the primitives and strings, so a `u32` field can convert into a `u64` field,
+/// or a `String` field into a `Box<str>` field. Other types become leaves by implementing
+/// `DeepTransformFrom<T, (), ConvertLeaves>`.
+pub struct ConvertLeaves;
+
 pub trait DeepTransform<T, I> {
     fn deep_transform(self) -> T;
 }
@@ -15,58 +31,424 @@ impl<T: DeepTransformFrom<U, I>, U, I> DeepTransform<T, I> for U {
     fn deep_transform(self) -> T { T::deep_transform_from(self) }
 }
 
+pub trait DeepConvert<T, I> {
+    fn deep_convert(self) -> T;
+}
+
+impl<T: DeepTransformFrom<U, I, ConvertLeaves>, U, I> DeepConvert<T, I> for U {
+    fn deep_convert(self) -> T { T::deep_transform_from(self) }
+}
+
+/// A fallible `DeepTransformFrom`
+///
+/// The error of an anon record is a coproduct with one variant per field, named after that field,
+/// and holding the error of the first field that failed to transform.
+pub trait TryDeepTransformFrom<T, I>: Sized {
+    type Error;
+
+    fn try_deep_transform_from(value: T) -> Result<Self, Self::Error>;
+}
+
+pub trait TryDeepTransform<T, I> {
+    type Error;
+
+    fn try_deep_transform(self) -> Result<T, Self::Error>;
+}
+
+impl<T: TryDeepTransformFrom<U, I>, U, I> TryDeepTransform<T, I> for U {
+    type Error = T::Error;
+
+    fn try_deep_transform(self) -> Result<T, Self::Error> { T::try_deep_transform_from(self) }
+}
+
 macro_rules! primitive {
     ($($primitive:ty),* $(,)?) => {
         $(
             impl DeepTransformFrom<$primitive, ()> for $primitive {
                 fn deep_transform_from(prim: $primitive) -> Self { prim }
             }
+
+            impl<T> DeepTransformFrom<T, (), ConvertLeaves> for $primitive
+            where
+                $primitive: From<T>,
+            {
+                fn deep_transform_from(value: T) -> Self { Self::from(value) }
+            }
+
+            impl TryDeepTransformFrom<$primitive, ()> for $primitive {
+                type Error = Infallible;
+
+                fn try_deep_transform_from(prim: $primitive) -> Result<Self, Self::Error> { Ok(prim) }
+            }
         )*
     };
 }
 
 primitive! { (), u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64, bool, char }
 
-impl<T, U: DeepTransformFrom<T, I>, I> DeepTransformFrom<Vec<T>, I> for Vec<U> {
-    fn deep_transform_from(value: Vec<T>) -> Self { value.into_iter().map(U::deep_transform_from).collect() }
+// references are leaves, which lets `DeepView` borrow fields
+impl<'a, T: ?Sized> DeepTransformFrom<&'a T, ()> for &'a T {
+    fn deep_transform_from(value: &'a T) -> Self { value }
+}
+
+impl<'a, T: ?Sized> TryDeepTransformFrom<&'a T, ()> for &'a T {
+    type Error = Infallible;
+
+    fn try_deep_transform_from(value: &'a T) -> Result<Self, Self::Error> { Ok(value) }
+}
+
+impl<'a, T> DeepTransformFrom<T, (), ConvertLeaves> for &'a str
+where
+    &'a str: From<T>,
+{
+    fn deep_transform_from(value: T) -> Self { Self::from(value) }
+}
+
+/// Deep transforms borrowed fields, `&'a Vec3` into `Anon!(x: &'a f32, y: &'a f32)`, without cloning
+///
+/// The fields are borrowed with `as_ref::AsRef`, which `#[derive(Transform)]` implements for structs.
+/// A reference to a struct can also stand in for an anon record of references to its fields,
+/// so nested structs can be viewed as well.
+pub trait DeepView<'a>: crate::as_ref::AsRef<'a> {
+    fn deep_view<O, N>(&'a self) -> O
+    where
+        Self::Ref: DeepTransform<O, N>,
+    {
+        self.as_ref().deep_transform()
+    }
+}
+
+impl<'a, T: ?Sized + crate::as_ref::AsRef<'a>> DeepView<'a> for T {}
+
+impl<T, U: DeepTransformFrom<T, I, M>, I, M> DeepTransformFrom<Option<T>, I, M> for Option<U> {
+    fn deep_transform_from(value: Option<T>) -> Self { value.map(U::deep_transform_from) }
+}
+
+impl<T, U: TryDeepTransformFrom<T, I>, I> TryDeepTransformFrom<Option<T>, I> for Option<U> {
+    type Error = U::Error;
+
+    fn try_deep_transform_from(value: Option<T>) -> Result<Self, Self::Error> {
+        value.map(U::try_deep_transform_from).transpose()
+    }
+}
+
+impl<T, U: DeepTransformFrom<T, I, M>, I, M, const N: usize> DeepTransformFrom<[T; N], I, M> for [U; N] {
+    fn deep_transform_from(value: [T; N]) -> Self { value.map(U::deep_transform_from) }
+}
+
+impl<T, U: TryDeepTransformFrom<T, I>, I, const N: usize> TryDeepTransformFrom<[T; N], I> for [U; N] {
+    type Error = U::Error;
+
+    fn try_deep_transform_from(value: [T; N]) -> Result<Self, Self::Error> {
+        let mut error = None;
+        let value = value.map(|value| match U::try_deep_transform_from(value) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                error.get_or_insert(err);
+                None
+            }
+        });
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(value.map(|value| value.expect("every element was transformed"))),
+        }
+    }
 }
 
-impl DeepTransformFrom<Nil, ()> for Nil {
+// a tuple transforms element-wise, and fails with the error of the first element that failed
+macro_rules! tuple {
+    ($(($T:ident $U:ident $I:ident $value:ident $P:ident))*) => {
+        impl<$($T, $U: DeepTransformFrom<$T, $I, M>, $I,)* M> DeepTransformFrom<($($T,)*), ($($I,)*), M> for ($($U,)*) {
+            fn deep_transform_from(($($value,)*): ($($T,)*)) -> Self { ($($U::deep_transform_from($value),)*) }
+        }
+
+        impl<$($T, $U: TryDeepTransformFrom<$T, $I>, $I),*> TryDeepTransformFrom<($($T,)*), ($($I,)*)> for ($($U,)*) {
+            type Error = crate::CoProd!($(<$U as TryDeepTransformFrom<$T, $I>>::Error),*);
+
+            fn try_deep_transform_from(($($value,)*): ($($T,)*)) -> Result<Self, Self::Error> {
+                Ok(($(
+                    match $U::try_deep_transform_from($value) {
+                        Ok(value) => value,
+                        Err(err) => return Err(<Self::Error as coprod::Access<_, crate::peano::$P>>::put(err)),
+                    },
+                )*))
+            }
+        }
+    };
+}
+
+macro_rules! tuples {
+    ([$($done:tt)*] $next:tt $($rest:tt)*) => {
+        tuple!($($done)* $next);
+        tuples!([$($done)* $next] $($rest)*);
+    };
+    ([$($done:tt)*]) => {};
+}
+
+tuples! {
+    []
+    (T0 U0 I0 v0 P0) (T1 U1 I1 v1 P1) (T2 U2 I2 v2 P2) (T3 U3 I3 v3 P3)
+    (T4 U4 I4 v4 P4) (T5 U5 I5 v5 P5) (T6 U6 I6 v6 P6) (T7 U7 I7 v7 P7)
+    (T8 U8 I8 v8 P8) (T9 U9 I9 v9 P9) (T10 U10 I10 v10 P10) (T11 U11 I11 v11 P11)
+}
+
+impl<M> DeepTransformFrom<Nil, (), M> for Nil {
     fn deep_transform_from(Nil: Self) -> Self { Self }
 }
 
-impl<T, R> DeepTransformFrom<Cons<T, R>, ()> for Nil {
+impl<T, R, M> DeepTransformFrom<Cons<T, R>, (), M> for Nil {
     fn deep_transform_from(_: Cons<T, R>) -> Self { Self }
 }
 
-impl<T, Rt, L, I, Ti, Ri, Name> DeepTransformFrom<L, (I, Ti, Ri)> for Cons<Named<T, Name>, Rt>
+impl<T, Rt, L, I, Ti, Ri, Name, M> DeepTransformFrom<L, (I, Ti, Ri), M> for Cons<Named<T, Name>, Rt>
 where
     L: super::RemoveField<Name, I>,
-    L::Value: DeepTransform<T, Ti>,
-    L::Remainder: DeepTransform<Rt, Ri>,
+    T: DeepTransformFrom<L::Value, Ti, M>,
+    Rt: DeepTransformFrom<L::Remainder, Ri, M>,
 {
     fn deep_transform_from(list: L) -> Self {
         let (value, rest) = list.remove_field();
         Self {
-            value: Named::new(value.deep_transform()),
-            rest: rest.deep_transform(),
+            value: Named::new(T::deep_transform_from(value)),
+            rest: Rt::deep_transform_from(rest),
         }
     }
 }
 
-impl<T, Rt, L: DeepTransform<Rt, Ri>, I, Ri> DeepTransformFrom<Cons<Unnamed<T>, L>, (I, Ri)> for Cons<Unnamed<T>, Rt> {
-    fn deep_transform_from(list: Cons<Unnamed<T>, L>) -> Self {
+impl<T, Rt, U, L, I, Ri, M> DeepTransformFrom<Cons<Unnamed<U>, L>, (I, Ri), M> for Cons<Unnamed<T>, Rt>
+where
+    T: DeepTransformFrom<U, I, M>,
+    Rt: DeepTransformFrom<L, Ri, M>,
+{
+    fn deep_transform_from(list: Cons<Unnamed<U>, L>) -> Self {
         Self {
-            value: list.value,
-            rest: list.rest.deep_transform(),
+            value: Unnamed(T::deep_transform_from(list.value.0)),
+            rest: Rt::deep_transform_from(list.rest),
         }
     }
 }
 
-impl<T, U: DeepTransform<T, I>, I, N> DeepTransformFrom<Named<U, N>, I> for Named<T, N> {
-    fn deep_transform_from(value: Named<U, N>) -> Self {
-        let value = value.0;
-        let value = value.deep_transform();
-        Self::new(value)
+impl<T: DeepTransformFrom<U, I, M>, U, I, N, M> DeepTransformFrom<Named<U, N>, I, M> for Named<T, N> {
+    fn deep_transform_from(value: Named<U, N>) -> Self { Self::new(T::deep_transform_from(value.0)) }
+}
+
+impl<M> DeepTransformFrom<CoNil, (), M> for CoNil {
+    fn deep_transform_from(value: CoNil) -> Self { value }
+}
+
+impl<T, R, M> DeepTransformFrom<CoNil, (), M> for CoCons<T, R> {
+    fn deep_transform_from(value: CoNil) -> Self { match value {} }
+}
+
+impl<T, Rt, U, Rs, I, Ui, Ri, Name, M> DeepTransformFrom<CoCons<Named<U, Name>, Rs>, (I, Ui, Ri), M> for CoCons<T, Rt>
+where
+    Self: InjectField<Name, I> + DeepTransformFrom<Rs, Ri, M>,
+    <Self as InjectField<Name, I>>::Value: DeepTransformFrom<U, Ui, M>,
+{
+    fn deep_transform_from(value: CoCons<Named<U, Name>, Rs>) -> Self {
+        match value {
+            CoCons::Value(value) => Self::inject_field(DeepTransformFrom::<U, Ui, M>::deep_transform_from(value.0)),
+            CoCons::Rest(rest) => DeepTransformFrom::<Rs, Ri, M>::deep_transform_from(rest),
+        }
     }
 }
+
+impl TryDeepTransformFrom<Nil, ()> for Nil {
+    type Error = CoNil;
+
+    fn try_deep_transform_from(Nil: Self) -> Result<Self, Self::Error> { Ok(Self) }
+}
+
+impl<T, R> TryDeepTransformFrom<Cons<T, R>, ()> for Nil {
+    type Error = CoNil;
+
+    fn try_deep_transform_from(_: Cons<T, R>) -> Result<Self, Self::Error> { Ok(Self) }
+}
+
+impl<T, Rt, L, I, Ti, Ri, Name> TryDeepTransformFrom<L, (I, Ti, Ri)> for Cons<Named<T, Name>, Rt>
+where
+    L: super::RemoveField<Name, I>,
+    L::Value: TryDeepTransform<T, Ti>,
+    L::Remainder: TryDeepTransform<Rt, Ri>,
+{
+    type Error = CoCons<Named<<L::Value as TryDeepTransform<T, Ti>>::Error, Name>, <L::Remainder as TryDeepTransform<Rt, Ri>>::Error>;
+
+    fn try_deep_transform_from(list: L) -> Result<Self, Self::Error> {
+        let (value, rest) = list.remove_field();
+        Ok(Self {
+            value: Named::new(value.try_deep_transform().map_err(|err| CoCons::Value(Named::new(err)))?),
+            rest: rest.try_deep_transform().map_err(CoCons::Rest)?,
+        })
+    }
+}
+
+// unnamed fields have no name to report, so their errors are listed by position
+impl<T, Rt, U, L, I, Ri> TryDeepTransformFrom<Cons<Unnamed<U>, L>, (I, Ri)> for Cons<Unnamed<T>, Rt>
+where
+    U: TryDeepTransform<T, I>,
+    L: TryDeepTransform<Rt, Ri>,
+{
+    type Error = CoCons<U::Error, L::Error>;
+
+    fn try_deep_transform_from(list: Cons<Unnamed<U>, L>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            value: Unnamed(list.value.0.try_deep_transform().map_err(CoCons::Value)?),
+            rest: list.rest.try_deep_transform().map_err(CoCons::Rest)?,
+        })
+    }
+}
+
+impl<T, U: TryDeepTransform<T, I>, I, N> TryDeepTransformFrom<Named<U, N>, I> for Named<T, N> {
+    type Error = U::Error;
+
+    fn try_deep_transform_from(value: Named<U, N>) -> Result<Self, Self::Error> { value.0.try_deep_transform().map(Self::new) }
+}
+
+impl TryDeepTransformFrom<CoNil, ()> for CoNil {
+    type Error = CoNil;
+
+    fn try_deep_transform_from(value: CoNil) -> Result<Self, Self::Error> { match value {} }
+}
+
+impl<T, R> TryDeepTransformFrom<CoNil, ()> for CoCons<T, R> {
+    type Error = CoNil;
+
+    fn try_deep_transform_from(value: CoNil) -> Result<Self, Self::Error> { match value {} }
+}
+
+impl<T, Rt, U, Rs, I, Ui, Ri, Name> TryDeepTransformFrom<CoCons<Named<U, Name>, Rs>, (I, Ui, Ri)> for CoCons<T, Rt>
+where
+    Self: InjectField<Name, I> + TryDeepTransformFrom<Rs, Ri>,
+    U: TryDeepTransform<<Self as InjectField<Name, I>>::Value, Ui>,
+{
+    type Error = CoCons<
+        Named<<U as TryDeepTransform<<Self as InjectField<Name, I>>::Value, Ui>>::Error, Name>,
+        <Self as TryDeepTransformFrom<Rs, Ri>>::Error,
+    >;
+
+    fn try_deep_transform_from(value: CoCons<Named<U, Name>, Rs>) -> Result<Self, Self::Error> {
+        match value {
+            CoCons::Value(value) => match value.0.try_deep_transform() {
+                Ok(value) => Ok(Self::inject_field(value)),
+                Err(err) => Err(CoCons::Value(Named::new(err))),
+            },
+            CoCons::Rest(rest) => Self::try_deep_transform_from(rest).map_err(CoCons::Rest),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod collections {
+    use std::{
+        boxed::Box,
+        collections::{BTreeMap, BTreeSet, LinkedList, VecDeque},
+        rc::Rc,
+        string::String,
+        vec::Vec,
+    };
+
+    use super::*;
+
+    impl DeepTransformFrom<String, ()> for String {
+        fn deep_transform_from(value: String) -> Self { value }
+    }
+
+    impl TryDeepTransformFrom<String, ()> for String {
+        type Error = Infallible;
+
+        fn try_deep_transform_from(value: String) -> Result<Self, Self::Error> { Ok(value) }
+    }
+
+    // owned strings convert between each other
+    macro_rules! string {
+        ($($(#[$meta:meta])* $string:ty),*) => {$(
+            $(#[$meta])*
+            impl<T> DeepTransformFrom<T, (), ConvertLeaves> for $string
+            where
+                $string: From<T>,
+            {
+                fn deep_transform_from(value: T) -> Self { Self::from(value) }
+            }
+        )*};
+    }
+
+    string! { String, Box<str>, Rc<str>, #[cfg(target_has_atomic = "ptr")] std::sync::Arc<str> }
+
+    // collections transform element-wise, and fail with the error of the first element that failed
+    macro_rules! collection {
+        ($($collection:ident $(: $bound:path)?),*) => {$(
+            impl<T, U: DeepTransformFrom<T, I, M> $(+ $bound)?, I, M> DeepTransformFrom<$collection<T>, I, M> for $collection<U> {
+                fn deep_transform_from(value: $collection<T>) -> Self {
+                    value.into_iter().map(U::deep_transform_from).collect()
+                }
+            }
+
+            impl<T, U: TryDeepTransformFrom<T, I> $(+ $bound)?, I> TryDeepTransformFrom<$collection<T>, I> for $collection<U> {
+                type Error = U::Error;
+
+                fn try_deep_transform_from(value: $collection<T>) -> Result<Self, Self::Error> {
+                    value.into_iter().map(U::try_deep_transform_from).collect()
+                }
+            }
+        )*};
+    }
+
+    collection! { Vec, VecDeque, LinkedList, BTreeSet: Ord }
+
+    // keys are kept as they are, only the values are transformed
+    impl<K: Ord, T, U: DeepTransformFrom<T, I, M>, I, M> DeepTransformFrom<BTreeMap<K, T>, I, M> for BTreeMap<K, U> {
+        fn deep_transform_from(value: BTreeMap<K, T>) -> Self {
+            value.into_iter().map(|(key, value)| (key, U::deep_transform_from(value))).collect()
+        }
+    }
+
+    impl<K: Ord, T, U: TryDeepTransformFrom<T, I>, I> TryDeepTransformFrom<BTreeMap<K, T>, I> for BTreeMap<K, U> {
+        type Error = U::Error;
+
+        fn try_deep_transform_from(value: BTreeMap<K, T>) -> Result<Self, Self::Error> {
+            value
+                .into_iter()
+                .map(|(key, value)| Ok((key, U::try_deep_transform_from(value)?)))
+                .collect()
+        }
+    }
+
+    impl<T, U: DeepTransformFrom<T, I, M>, I, M> DeepTransformFrom<Box<T>, I, M> for Box<U> {
+        fn deep_transform_from(value: Box<T>) -> Self { Box::new(U::deep_transform_from(*value)) }
+    }
+
+    impl<T, U: TryDeepTransformFrom<T, I>, I> TryDeepTransformFrom<Box<T>, I> for Box<U> {
+        type Error = U::Error;
+
+        fn try_deep_transform_from(value: Box<T>) -> Result<Self, Self::Error> {
+            U::try_deep_transform_from(*value).map(Box::new)
+        }
+    }
+
+    // shared pointers clone their value if it is still shared
+    macro_rules! shared {
+        ($($(#[$meta:meta])* $shared:ident),*) => {$(
+            $(#[$meta])*
+            impl<T: Clone, U: DeepTransformFrom<T, I, M>, I, M> DeepTransformFrom<$shared<T>, I, M> for $shared<U> {
+                fn deep_transform_from(value: $shared<T>) -> Self {
+                    $shared::new(U::deep_transform_from($shared::unwrap_or_clone(value)))
+                }
+            }
+
+            $(#[$meta])*
+            impl<T: Clone, U: TryDeepTransformFrom<T, I>, I> TryDeepTransformFrom<$shared<T>, I> for $shared<U> {
+                type Error = U::Error;
+
+                fn try_deep_transform_from(value: $shared<T>) -> Result<Self, Self::Error> {
+                    U::try_deep_transform_from($shared::unwrap_or_clone(value)).map($shared::new)
+                }
+            }
+        )*};
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    use std::sync::Arc;
+
+    shared! { Rc, #[cfg(target_has_atomic = "ptr")] Arc }
+}
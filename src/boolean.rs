@@ -0,0 +1,74 @@
+use crate::hlist::{Cons, Nil};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum True {}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum False {}
+
+pub trait Bool: crate::Seal {
+    const VALUE: bool;
+}
+
+impl crate::Seal for True {}
+impl Bool for True {
+    const VALUE: bool = true;
+}
+
+impl crate::Seal for False {}
+impl Bool for False {
+    const VALUE: bool = false;
+}
+
+pub trait And<B: Bool>: Bool {
+    type Output: Bool;
+}
+
+impl<B: Bool> And<B> for True {
+    type Output = B;
+}
+
+impl<B: Bool> And<B> for False {
+    type Output = False;
+}
+
+/// Type-level equality, `Output` is `True` if `Self` and `T` are equal
+///
+/// Booleans compare by value, and hlists compare element-wise.
+pub trait Equal<T> {
+    type Output: Bool;
+}
+
+impl Equal<True> for True {
+    type Output = True;
+}
+
+impl Equal<False> for True {
+    type Output = False;
+}
+
+impl Equal<True> for False {
+    type Output = False;
+}
+
+impl Equal<False> for False {
+    type Output = True;
+}
+
+impl Equal<Nil> for Nil {
+    type Output = True;
+}
+
+impl<U, S> Equal<Cons<U, S>> for Nil {
+    type Output = False;
+}
+
+impl<T, R> Equal<Nil> for Cons<T, R> {
+    type Output = False;
+}
+
+impl<T: Equal<U>, R: Equal<S>, U, S> Equal<Cons<U, S>> for Cons<T, R>
+where
+    T::Output: And<R::Output>,
+{
+    type Output = <T::Output as And<R::Output>>::Output;
+}
@@ -1,7 +1,16 @@
 use crate::hlist::{Cons, HList, Nil};
 
-include!(concat!(env!("OUT_DIR"), "/convert_tuple.rs"));
+#[cfg(feature = "macros")]
+pub use macros::Generic;
 
+#[allow(clippy::unused_unit)]
+mod convert_tuple {
+    use super::*;
+
+    include!(concat!(env!("OUT_DIR"), "/convert_tuple.rs"));
+}
+
+/// Converts to and from an hlist of the fields, in declaration order
 pub trait Convert {
     type HList: HList;
 
@@ -43,6 +43,50 @@ impl<T: Uninhabitted, R: Uninhabitted> Uninhabitted for CoCons<T, R> {
     }
 }
 
+pub trait Variant: CoProd {
+    fn variant_index(&self) -> usize;
+
+    fn variant_type_name(&self) -> &'static str;
+
+    fn variant_type_id(&self) -> core::any::TypeId
+    where
+        Self: 'static;
+}
+
+impl Variant for CoNil {
+    fn variant_index(&self) -> usize { match *self {} }
+
+    fn variant_type_name(&self) -> &'static str { match *self {} }
+
+    fn variant_type_id(&self) -> core::any::TypeId { match *self {} }
+}
+
+impl<T, R: Variant> Variant for CoCons<T, R> {
+    fn variant_index(&self) -> usize {
+        match self {
+            Self::Value(_) => 0,
+            Self::Rest(rest) => 1 + rest.variant_index(),
+        }
+    }
+
+    fn variant_type_name(&self) -> &'static str {
+        match self {
+            Self::Value(_) => core::any::type_name::<T>(),
+            Self::Rest(rest) => rest.variant_type_name(),
+        }
+    }
+
+    fn variant_type_id(&self) -> core::any::TypeId
+    where
+        Self: 'static,
+    {
+        match self {
+            Self::Value(_) => core::any::TypeId::of::<T>(),
+            Self::Rest(rest) => rest.variant_type_id(),
+        }
+    }
+}
+
 pub trait Access<T, N>: CoProd {
     type Remainder: CoProd;
 
@@ -136,6 +180,28 @@ where
     }
 }
 
+/// Embeds a coproduct into a superset by explicit target positions
+///
+/// `Indices` is a `TyList!` of `peano` positions in `T`, one per variant of `Self`.
+/// Unlike `IntoSuperset::into_superset`, this can target coproducts that repeat a type.
+///
+/// ```
+/// # use typsy::{coprod::{CoCons, Embed}, peano::*, CoProd, TyList};
+/// let err: CoProd!(u8, &str) = CoCons::Value(1);
+/// let err: CoProd!(u8, &str, u8) = err.embed::<_, TyList!(P2, P1)>();
+/// assert_eq!(err, CoCons::Rest(CoCons::Rest(CoCons::Value(1))));
+/// ```
+pub trait Embed: CoProd {
+    fn embed<T: CoProd, Indices>(self) -> T
+    where
+        Self: IntoSuperset<T, Indices>,
+    {
+        self.into_superset()
+    }
+}
+
+impl<T: CoProd> Embed for T {}
+
 impl<T: CoProd> IntoSubset<CoNil, crate::TyList!()> for T {
     type Remainder = Self;
 
@@ -170,6 +236,109 @@ pub trait Shuffle<T: CoProd, N>: IntoSubset<T, N, Remainder = CoNil> {
     }
 }
 
+/// Views the active variant of a coproduct as a trait object
+///
+/// Implementations are generated by [`coprod_as!`](crate::coprod_as), which must be invoked
+/// in the crate that defines the trait. `typsy` provides them for `dyn Debug`, `dyn Display`
+/// and `dyn Any`.
+pub trait CoProdAs<T: ?Sized>: CoProd {
+    fn as_dyn(&self) -> &T;
+
+    fn as_dyn_mut(&mut self) -> &mut T;
+
+    #[cfg(feature = "alloc")]
+    fn into_dyn(self) -> std::boxed::Box<T>;
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "alloc")]
+macro_rules! __coprod_as_alloc {
+    ($($item:tt)*) => { $($item)* };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "alloc"))]
+macro_rules! __coprod_as_alloc {
+    ($($item:tt)*) => {};
+}
+
+/// Implements [`CoProdAs`] for every coproduct whose variants implement a trait
+///
+/// Every variant must be `'static`, as the trait object is `dyn Trait + 'static`.
+///
+/// ```
+/// # use typsy::{coprod::{CoCons, CoProdAs}, coprod_as, CoProd};
+/// trait Event {
+///     fn name(&self) -> &'static str;
+/// }
+///
+/// coprod_as!(dyn Event);
+///
+/// struct Click;
+/// struct Scroll;
+///
+/// impl Event for Click {
+///     fn name(&self) -> &'static str { "click" }
+/// }
+///
+/// impl Event for Scroll {
+///     fn name(&self) -> &'static str { "scroll" }
+/// }
+///
+/// let event: CoProd!(Click, Scroll) = CoCons::Rest(CoCons::Value(Scroll));
+/// let event: &dyn Event = event.as_dyn();
+/// assert_eq!(event.name(), "scroll");
+/// ```
+#[macro_export]
+macro_rules! coprod_as {
+    ($( [ $($generics:tt)* ] )? dyn $($bound:tt)+) => {
+        impl $(<$($generics)*>)? $crate::coprod::CoProdAs<dyn $($bound)+> for $crate::coprod::CoNil {
+            fn as_dyn(&self) -> &(dyn $($bound)+ + 'static) { match *self {} }
+
+            fn as_dyn_mut(&mut self) -> &mut (dyn $($bound)+ + 'static) { match *self {} }
+
+            $crate::__coprod_as_alloc! {
+                fn into_dyn(self) -> $crate::alloc::Box<dyn $($bound)+ + 'static> { match self {} }
+            }
+        }
+
+        impl<$($($generics)*,)? __TypsyV, __TypsyR> $crate::coprod::CoProdAs<dyn $($bound)+> for $crate::coprod::CoCons<__TypsyV, __TypsyR>
+        where
+            __TypsyV: $($bound)+ + 'static,
+            __TypsyR: $crate::coprod::CoProdAs<dyn $($bound)+>,
+        {
+            fn as_dyn(&self) -> &(dyn $($bound)+ + 'static) {
+                match self {
+                    $crate::coprod::CoCons::Value(value) => value,
+                    $crate::coprod::CoCons::Rest(rest) => rest.as_dyn(),
+                }
+            }
+
+            fn as_dyn_mut(&mut self) -> &mut (dyn $($bound)+ + 'static) {
+                match self {
+                    $crate::coprod::CoCons::Value(value) => value,
+                    $crate::coprod::CoCons::Rest(rest) => rest.as_dyn_mut(),
+                }
+            }
+
+            $crate::__coprod_as_alloc! {
+                fn into_dyn(self) -> $crate::alloc::Box<dyn $($bound)+ + 'static> {
+                    match self {
+                        $crate::coprod::CoCons::Value(value) => $crate::alloc::Box::new(value),
+                        $crate::coprod::CoCons::Rest(rest) => rest.into_dyn(),
+                    }
+                }
+            }
+        }
+    };
+}
+
+crate::coprod_as!(dyn core::fmt::Debug);
+crate::coprod_as!(dyn core::fmt::Display);
+crate::coprod_as!(dyn core::any::Any);
+
 pub trait AnonResult<Err, N> {
     type Ok;
 
@@ -1,8 +1,10 @@
+use core::fmt;
+
 use crate::peano;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Nil;
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Cons<T, R> {
     pub value: T,
     pub rest: R,
@@ -18,6 +20,81 @@ impl<T, R: HList> HList for Cons<T, R> {}
 pub trait NonEmpty: HList {}
 impl<T, R: HList> NonEmpty for Cons<T, R> {}
 
+#[doc(hidden)]
+pub trait DebugElements {
+    /// set when an element is a named anon field, the hlist then prints as a map, `{ x: 1.0, y: 2.0 }`
+    const NAMED: bool;
+
+    /// calls `entry` with the key of each element, its name or its position, and the element
+    fn debug_elements(
+        &self,
+        position: usize,
+        entry: &mut dyn FnMut(&dyn fmt::Debug, &dyn fmt::Debug) -> fmt::Result,
+    ) -> fmt::Result;
+}
+
+impl DebugElements for Nil {
+    const NAMED: bool = false;
+
+    fn debug_elements(&self, _: usize, _: &mut dyn FnMut(&dyn fmt::Debug, &dyn fmt::Debug) -> fmt::Result) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug, R: DebugElements> DebugElements for Cons<T, R> {
+    const NAMED: bool = R::NAMED;
+
+    fn debug_elements(
+        &self,
+        position: usize,
+        entry: &mut dyn FnMut(&dyn fmt::Debug, &dyn fmt::Debug) -> fmt::Result,
+    ) -> fmt::Result {
+        entry(&position, &self.value)?;
+        self.rest.debug_elements(position + 1, entry)
+    }
+}
+
+impl fmt::Debug for Nil {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.debug_list().finish() }
+}
+
+impl<T, R> fmt::Debug for Cons<T, R>
+where
+    Self: DebugElements,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !Self::NAMED {
+            let mut list = f.debug_list();
+            self.debug_elements(0, &mut |_, value| {
+                list.entry(value);
+                Ok(())
+            })?;
+            return list.finish()
+        }
+
+        if f.alternate() {
+            let mut map = f.debug_map();
+            self.debug_elements(0, &mut |key, value| {
+                map.entry(key, value);
+                Ok(())
+            })?;
+            return map.finish()
+        }
+
+        let mut first = true;
+        f.write_str("{ ")?;
+        self.debug_elements(0, &mut |key, value| {
+            if !core::mem::take(&mut first) {
+                f.write_str(", ")?;
+            }
+            key.fmt(f)?;
+            f.write_str(": ")?;
+            value.fmt(f)
+        })?;
+        f.write_str(" }")
+    }
+}
+
 #[macro_export]
 macro_rules! HList {
     () => { $crate::hlist::Nil };
@@ -109,16 +186,20 @@ pub trait Shuffle<T: HList, N>: Split<T, N, Remainder = Nil> {
     }
 }
 
-pub trait Concat<T: HList>: HList {
+pub trait Concat<T: HList>: HList + Sized {
     type Output: HList;
 
     fn concat(self, other: T) -> Self::Output;
+
+    fn unconcat(output: Self::Output) -> (Self, T);
 }
 
 impl<T: HList> Concat<T> for Nil {
     type Output = T;
 
     fn concat(self, other: T) -> Self::Output { other }
+
+    fn unconcat(output: Self::Output) -> (Self, T) { (Self, output) }
 }
 
 impl<T, R: Concat<L>, L: HList> Concat<L> for Cons<T, R> {
@@ -130,4 +211,15 @@ impl<T, R: Concat<L>, L: HList> Concat<L> for Cons<T, R> {
             rest: self.rest.concat(other),
         }
     }
+
+    fn unconcat(output: Self::Output) -> (Self, L) {
+        let (rest, other) = R::unconcat(output.rest);
+        (
+            Cons {
+                value: output.value,
+                rest,
+            },
+            other,
+        )
+    }
 }
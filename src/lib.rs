@@ -9,12 +9,19 @@ extern crate alloc as std;
 #[doc(hidden)]
 pub use core;
 
+#[doc(hidden)]
+#[cfg(feature = "alloc")]
+pub mod alloc {
+    pub use std::boxed::Box;
+}
+
 #[doc(hidden)]
 #[cfg(feature = "macros")]
 pub use macros;
 
 #[cfg(feature = "macros")]
 pub mod anon;
+pub mod boolean;
 pub mod call;
 pub mod coprod;
 pub mod hlist;
@@ -24,10 +31,14 @@ pub mod as_ref;
 pub mod cmp;
 pub mod fold;
 pub mod map;
+pub mod ops;
 pub mod zip;
 
 pub mod convert;
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
 use seal::Seal;
 mod seal {
     pub trait Seal {}
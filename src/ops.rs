@@ -0,0 +1,79 @@
+//! Field-wise operations, which the `TypsyAdd`, `TypsyDefault` and `TypsyMerge` derives
+//! implement through `convert::Convert`
+
+use crate::hlist::{Cons, Nil};
+
+#[cfg(feature = "macros")]
+pub use macros::{TypsyAdd, TypsyDefault, TypsyMerge};
+
+/// Adds the two halves of a pair, for mapping over zipped hlists
+pub struct Add;
+
+crate::call! {
+    fn[T: core::ops::Add](&self: Add, pair: (T, T)) -> T::Output { pair.0 + pair.1 }
+}
+
+/// An associative way to combine two values
+///
+/// Numbers add, `Option` combines when both are `Some`, strings and vectors concatenate,
+/// and hlists combine element-wise.
+pub trait Semigroup {
+    fn combine(self, other: Self) -> Self;
+}
+
+macro_rules! number {
+    ($($number:ty),* $(,)?) => {$(
+        impl Semigroup for $number {
+            fn combine(self, other: Self) -> Self { self + other }
+        }
+    )*};
+}
+
+number! { u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64 }
+
+impl Semigroup for () {
+    fn combine(self, (): Self) -> Self {}
+}
+
+impl<T: Semigroup> Semigroup for Option<T> {
+    fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(value), Some(other)) => Some(value.combine(other)),
+            (value, None) | (None, value) => value,
+        }
+    }
+}
+
+impl Semigroup for Nil {
+    fn combine(self, Nil: Self) -> Self { Self }
+}
+
+impl<T: Semigroup, R: Semigroup> Semigroup for Cons<T, R> {
+    fn combine(self, other: Self) -> Self {
+        Self {
+            value: self.value.combine(other.value),
+            rest: self.rest.combine(other.rest),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod collections {
+    use std::{string::String, vec::Vec};
+
+    use super::Semigroup;
+
+    impl Semigroup for String {
+        fn combine(mut self, other: Self) -> Self {
+            self.push_str(&other);
+            self
+        }
+    }
+
+    impl<T> Semigroup for Vec<T> {
+        fn combine(mut self, mut other: Self) -> Self {
+            self.append(&mut other);
+            self
+        }
+    }
+}
@@ -1,2 +1,19 @@
 pub enum Zero {}
 pub struct Succ<N>(N);
+
+macro_rules! aliases {
+    ($prev:ident $($next:ident)*) => {
+        pub type $prev = Zero;
+        aliases!(@succ $prev $($next)*);
+    };
+    (@succ $prev:ident $next:ident $($rest:ident)*) => {
+        pub type $next = Succ<$prev>;
+        aliases!(@succ $next $($rest)*);
+    };
+    (@succ $prev:ident) => {};
+}
+
+aliases! {
+    P0 P1 P2 P3 P4 P5 P6 P7 P8 P9 P10 P11 P12 P13 P14 P15
+    P16 P17 P18 P19 P20 P21 P22 P23 P24 P25 P26 P27 P28 P29 P30 P31
+}
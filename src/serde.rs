@@ -0,0 +1,588 @@
+//! `serde` support
+//!
+//! * hlists serialize as tuples (sequences)
+//! * anon records with named fields serialize as maps, keyed by field name
+//! * coproducts serialize as externally tagged enums, variants of `Named` are tagged by name, the canon of enums,
+//!   and other variants by their index in decimal, `{"2":3}`
+
+use core::{fmt, marker::PhantomData};
+
+use ::serde::{
+    de::{self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess},
+    ser::{self, Serialize, SerializeMap, SerializeTuple, Serializer},
+};
+
+use crate::{
+    coprod::{CoCons, CoNil},
+    hlist::{Cons, Nil},
+};
+
+//
+// hlist
+//
+
+#[doc(hidden)]
+pub trait SerializeElements {
+    const LEN: usize;
+
+    fn serialize_elements<S: SerializeTuple>(&self, tuple: &mut S) -> Result<(), S::Error>;
+}
+
+#[doc(hidden)]
+pub trait DeserializeElements<'de>: Sized {
+    const LEN: usize;
+
+    fn deserialize_elements<A: SeqAccess<'de>>(
+        seq: &mut A,
+        index: usize,
+        expected: &dyn de::Expected,
+    ) -> Result<Self, A::Error>;
+}
+
+impl SerializeElements for Nil {
+    const LEN: usize = 0;
+
+    fn serialize_elements<S: SerializeTuple>(&self, _: &mut S) -> Result<(), S::Error> { Ok(()) }
+}
+
+impl<T: Serialize, R: SerializeElements> SerializeElements for Cons<T, R> {
+    const LEN: usize = 1 + R::LEN;
+
+    fn serialize_elements<S: SerializeTuple>(&self, tuple: &mut S) -> Result<(), S::Error> {
+        tuple.serialize_element(&self.value)?;
+        self.rest.serialize_elements(tuple)
+    }
+}
+
+impl<'de> DeserializeElements<'de> for Nil {
+    const LEN: usize = 0;
+
+    fn deserialize_elements<A: SeqAccess<'de>>(_: &mut A, _: usize, _: &dyn de::Expected) -> Result<Self, A::Error> {
+        Ok(Self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, R: DeserializeElements<'de>> DeserializeElements<'de> for Cons<T, R> {
+    const LEN: usize = 1 + R::LEN;
+
+    fn deserialize_elements<A: SeqAccess<'de>>(
+        seq: &mut A,
+        index: usize,
+        expected: &dyn de::Expected,
+    ) -> Result<Self, A::Error> {
+        let value = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(index, expected))?;
+        let rest = R::deserialize_elements(seq, index + 1, expected)?;
+        Ok(Self { value, rest })
+    }
+}
+
+struct ElementsVisitor<L>(PhantomData<L>);
+
+impl<'de, L: DeserializeElements<'de>> de::Visitor<'de> for ElementsVisitor<L> {
+    type Value = L;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "a sequence of {} elements", L::LEN) }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        L::deserialize_elements(&mut seq, 0, &self)
+    }
+}
+
+impl Serialize for Nil {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_tuple(0)?.end()
+    }
+}
+
+impl<T: Serialize, R: SerializeElements> Serialize for Cons<T, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(Self::LEN)?;
+        self.serialize_elements(&mut tuple)?;
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Nil {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(0, ElementsVisitor(PhantomData))
+    }
+}
+
+impl<'de, T: Deserialize<'de>, R: DeserializeElements<'de>> Deserialize<'de> for Cons<T, R> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(Self::LEN, ElementsVisitor(PhantomData))
+    }
+}
+
+//
+// anon records
+//
+
+#[cfg(feature = "macros")]
+mod anon {
+    use super::*;
+
+    use crate::anon::{DisplayName, FieldName, Named, Unnamed};
+
+    impl<Name: FieldName> Serialize for DisplayName<Name> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { serializer.collect_str(self) }
+    }
+
+    impl<T: Serialize> Serialize for Unnamed<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { self.0.serialize(serializer) }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for Unnamed<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Unnamed)
+        }
+    }
+
+    #[doc(hidden)]
+    pub trait SerializeFields {
+        const LEN: usize;
+
+        fn serialize_fields<S: SerializeMap>(&self, map: &mut S) -> Result<(), S::Error>;
+    }
+
+    #[doc(hidden)]
+    pub trait DeserializeFields<'de>: Sized {
+        type Slots;
+
+        fn slots() -> Self::Slots;
+
+        fn find(key: &str, index: usize) -> Option<usize>;
+
+        fn deserialize_field<A: MapAccess<'de>>(
+            slots: &mut Self::Slots,
+            index: usize,
+            map: &mut A,
+        ) -> Result<(), A::Error>;
+
+        fn finish<E: de::Error>(slots: Self::Slots) -> Result<Self, E>;
+    }
+
+    impl SerializeFields for Nil {
+        const LEN: usize = 0;
+
+        fn serialize_fields<S: SerializeMap>(&self, _: &mut S) -> Result<(), S::Error> { Ok(()) }
+    }
+
+    impl<T: Serialize, Name: FieldName, R: SerializeFields> SerializeFields for Cons<Named<T, Name>, R> {
+        const LEN: usize = 1 + R::LEN;
+
+        fn serialize_fields<S: SerializeMap>(&self, map: &mut S) -> Result<(), S::Error> {
+            map.serialize_entry(&DisplayName::<Name>::new(), &self.value.0)?;
+            self.rest.serialize_fields(map)
+        }
+    }
+
+    impl<'de> DeserializeFields<'de> for Nil {
+        type Slots = Nil;
+
+        fn slots() -> Self::Slots { Nil }
+
+        fn find(_: &str, _: usize) -> Option<usize> { None }
+
+        fn deserialize_field<A: MapAccess<'de>>(_: &mut Self::Slots, _: usize, _: &mut A) -> Result<(), A::Error> {
+            Ok(())
+        }
+
+        fn finish<E: de::Error>(Nil: Self::Slots) -> Result<Self, E> { Ok(Nil) }
+    }
+
+    impl<'de, T: Deserialize<'de>, Name: FieldName, R: DeserializeFields<'de>> DeserializeFields<'de>
+        for Cons<Named<T, Name>, R>
+    {
+        type Slots = Cons<Option<T>, R::Slots>;
+
+        fn slots() -> Self::Slots {
+            Cons {
+                value: None,
+                rest: R::slots(),
+            }
+        }
+
+        fn find(key: &str, index: usize) -> Option<usize> {
+            if Name::matches(key) {
+                Some(index)
+            } else {
+                R::find(key, index + 1)
+            }
+        }
+
+        fn deserialize_field<A: MapAccess<'de>>(
+            slots: &mut Self::Slots,
+            index: usize,
+            map: &mut A,
+        ) -> Result<(), A::Error> {
+            if index != 0 {
+                return R::deserialize_field(&mut slots.rest, index - 1, map)
+            }
+
+            if slots.value.is_some() {
+                return Err(de::Error::custom(format_args!(
+                    "duplicate field `{}`",
+                    DisplayName::<Name>::new()
+                )))
+            }
+
+            slots.value = Some(map.next_value()?);
+            Ok(())
+        }
+
+        fn finish<E: de::Error>(slots: Self::Slots) -> Result<Self, E> {
+            let value = slots
+                .value
+                .ok_or_else(|| de::Error::custom(format_args!("missing field `{}`", DisplayName::<Name>::new())))?;
+            Ok(Self {
+                value: Named::new(value),
+                rest: R::finish(slots.rest)?,
+            })
+        }
+    }
+
+    struct FieldSeed<L>(PhantomData<L>);
+
+    impl<'de, L: DeserializeFields<'de>> DeserializeSeed<'de> for FieldSeed<L> {
+        type Value = Option<usize>;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_identifier(self)
+        }
+    }
+
+    impl<'de, L: DeserializeFields<'de>> de::Visitor<'de> for FieldSeed<L> {
+        type Value = Option<usize>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("a field name") }
+
+        fn visit_str<E: de::Error>(self, key: &str) -> Result<Self::Value, E> { Ok(L::find(key, 0)) }
+    }
+
+    struct FieldsVisitor<L>(PhantomData<L>);
+
+    impl<'de, L: DeserializeFields<'de>> de::Visitor<'de> for FieldsVisitor<L> {
+        type Value = L;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("an anonymous record") }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut slots = L::slots();
+
+            while let Some(index) = map.next_key_seed(FieldSeed::<L>(PhantomData))? {
+                match index {
+                    Some(index) => L::deserialize_field(&mut slots, index, &mut map)?,
+                    None => {
+                        map.next_value::<de::IgnoredAny>()?;
+                    }
+                }
+            }
+
+            L::finish(slots)
+        }
+    }
+
+    impl<T: Serialize, Name: FieldName, R: SerializeFields> Serialize for Cons<Named<T, Name>, R> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(Self::LEN))?;
+            self.serialize_fields(&mut map)?;
+            map.end()
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>, Name: FieldName, R: DeserializeFields<'de>> Deserialize<'de> for Cons<Named<T, Name>, R> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_map(FieldsVisitor(PhantomData))
+        }
+    }
+
+    //
+    // named coproducts
+    //
+
+    #[doc(hidden)]
+    pub trait SerializeNamedVariant {
+        fn serialize_named_variant<S: Serializer>(&self, index: u32, serializer: S) -> Result<S::Ok, S::Error>;
+    }
+
+    #[doc(hidden)]
+    pub trait DeserializeNamedVariant<'de>: Sized {
+        const LEN: u32;
+
+        fn find(name: &str, index: u32) -> Option<u32>;
+
+        fn deserialize_named_variant<A: VariantAccess<'de>>(index: u32, variant: A) -> Result<Self, A::Error>;
+    }
+
+    impl SerializeNamedVariant for CoNil {
+        fn serialize_named_variant<S: Serializer>(&self, _: u32, _: S) -> Result<S::Ok, S::Error> { match *self {} }
+    }
+
+    impl<T: Serialize, Name: FieldName, R: SerializeNamedVariant> SerializeNamedVariant for CoCons<Named<T, Name>, R> {
+        fn serialize_named_variant<S: Serializer>(&self, index: u32, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Self::Value(value) => serializer.serialize_newtype_variant(ENUM, index, Name::NAME, &value.0),
+                Self::Rest(rest) => rest.serialize_named_variant(index + 1, serializer),
+            }
+        }
+    }
+
+    impl<'de> DeserializeNamedVariant<'de> for CoNil {
+        const LEN: u32 = 0;
+
+        fn find(_: &str, _: u32) -> Option<u32> { None }
+
+        fn deserialize_named_variant<A: VariantAccess<'de>>(_: u32, _: A) -> Result<Self, A::Error> {
+            Err(de::Error::custom("variant index out of range"))
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>, Name: FieldName, R: DeserializeNamedVariant<'de>> DeserializeNamedVariant<'de>
+        for CoCons<Named<T, Name>, R>
+    {
+        const LEN: u32 = 1 + R::LEN;
+
+        fn find(name: &str, index: u32) -> Option<u32> {
+            if Name::matches(name) {
+                Some(index)
+            } else {
+                R::find(name, index + 1)
+            }
+        }
+
+        fn deserialize_named_variant<A: VariantAccess<'de>>(index: u32, variant: A) -> Result<Self, A::Error> {
+            match index {
+                0 => variant.newtype_variant().map(|value| Self::Value(Named::new(value))),
+                _ => R::deserialize_named_variant(index - 1, variant).map(Self::Rest),
+            }
+        }
+    }
+
+    struct NamedVariantSeed<C>(PhantomData<C>);
+
+    impl<'de, C: DeserializeNamedVariant<'de>> DeserializeSeed<'de> for NamedVariantSeed<C> {
+        type Value = u32;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_identifier(self)
+        }
+    }
+
+    impl<'de, C: DeserializeNamedVariant<'de>> de::Visitor<'de> for NamedVariantSeed<C> {
+        type Value = u32;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("a variant name") }
+
+        fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+            C::find(name, 0).ok_or_else(|| de::Error::custom(format_args!("unknown variant `{}`", name)))
+        }
+
+        fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+            if index < u64::from(C::LEN) {
+                Ok(index as u32)
+            } else {
+                Err(de::Error::invalid_value(de::Unexpected::Unsigned(index), &"a variant index in range"))
+            }
+        }
+    }
+
+    struct NamedVariantVisitor<C>(PhantomData<C>);
+
+    impl<'de, C: DeserializeNamedVariant<'de>> de::Visitor<'de> for NamedVariantVisitor<C> {
+        type Value = C;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("an enum") }
+
+        fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+            let (index, variant) = data.variant_seed(NamedVariantSeed::<C>(PhantomData))?;
+            C::deserialize_named_variant(index, variant)
+        }
+    }
+
+    impl<T: Serialize, Name: FieldName, R: SerializeNamedVariant> Serialize for CoCons<Named<T, Name>, R> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.serialize_named_variant(0, serializer)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>, Name: FieldName, R: DeserializeNamedVariant<'de>> Deserialize<'de>
+        for CoCons<Named<T, Name>, R>
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_enum(ENUM, &[], NamedVariantVisitor(PhantomData))
+        }
+    }
+}
+
+#[cfg(feature = "macros")]
+pub use anon::{DeserializeFields, DeserializeNamedVariant, SerializeFields, SerializeNamedVariant};
+
+//
+// coprod, coproducts of `Named` variants are in `anon`
+//
+
+/// the name given to serializers for coproducts, which don't know their enum's name
+const ENUM: &str = "CoProd";
+
+/// the most variants a plain coproduct can have and still be serialized
+const MAX_VARIANTS: usize = 1000;
+
+/// the decimal indices `0..MAX_VARIANTS` back to back, since serializers take variant names as `&'static str`
+static VARIANT_NAMES: [u8; VARIANT_NAMES_LEN] = variant_names();
+
+const VARIANT_NAMES_LEN: usize = 10 + 2 * 90 + 3 * 900;
+
+const fn variant_names() -> [u8; VARIANT_NAMES_LEN] {
+    let mut names = [0; VARIANT_NAMES_LEN];
+    let mut len = 0;
+    let mut index = 0;
+    while index < MAX_VARIANTS {
+        let digits = variant_name_len(index);
+        let mut rest = index;
+        let mut i = digits;
+        while i > 0 {
+            i -= 1;
+            names[len + i] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+        }
+        len += digits;
+        index += 1;
+    }
+    names
+}
+
+const fn variant_name_len(index: usize) -> usize {
+    match index {
+        0..=9 => 1,
+        10..=99 => 2,
+        _ => 3,
+    }
+}
+
+/// the index of a plain coproduct's variant in decimal, its variant name
+fn variant_name(index: u32) -> Option<&'static str> {
+    let index = index as usize;
+    let start = match index {
+        0..=9 => index,
+        10..=99 => 10 + 2 * (index - 10),
+        100..=999 => 190 + 3 * (index - 100),
+        _ => return None,
+    };
+    core::str::from_utf8(&VARIANT_NAMES[start..start + variant_name_len(index)]).ok()
+}
+
+#[doc(hidden)]
+pub trait SerializeVariant {
+    fn serialize_variant<S: Serializer>(&self, index: u32, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+#[doc(hidden)]
+pub trait DeserializeVariant<'de>: Sized {
+    const LEN: u32;
+
+    fn deserialize_variant<A: VariantAccess<'de>>(index: u32, variant: A) -> Result<Self, A::Error>;
+}
+
+impl SerializeVariant for CoNil {
+    fn serialize_variant<S: Serializer>(&self, _: u32, _: S) -> Result<S::Ok, S::Error> { match *self {} }
+}
+
+impl<T: Serialize, R: SerializeVariant> SerializeVariant for CoCons<T, R> {
+    fn serialize_variant<S: Serializer>(&self, index: u32, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Value(value) => {
+                let name = variant_name(index).ok_or_else(|| {
+                    ser::Error::custom(format_args!("coproducts are limited to {} variants", MAX_VARIANTS))
+                })?;
+                serializer.serialize_newtype_variant(ENUM, index, name, value)
+            }
+            Self::Rest(rest) => rest.serialize_variant(index + 1, serializer),
+        }
+    }
+}
+
+impl<'de> DeserializeVariant<'de> for CoNil {
+    const LEN: u32 = 0;
+
+    fn deserialize_variant<A: VariantAccess<'de>>(_: u32, _: A) -> Result<Self, A::Error> {
+        Err(de::Error::custom("variant index out of range"))
+    }
+}
+
+impl<'de, T: Deserialize<'de>, R: DeserializeVariant<'de>> DeserializeVariant<'de> for CoCons<T, R> {
+    const LEN: u32 = 1 + R::LEN;
+
+    fn deserialize_variant<A: VariantAccess<'de>>(index: u32, variant: A) -> Result<Self, A::Error> {
+        match index {
+            0 => variant.newtype_variant().map(Self::Value),
+            _ => R::deserialize_variant(index - 1, variant).map(Self::Rest),
+        }
+    }
+}
+
+struct VariantSeed<C>(PhantomData<C>);
+
+impl<'de, C: DeserializeVariant<'de>> DeserializeSeed<'de> for VariantSeed<C> {
+    type Value = u32;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de, C: DeserializeVariant<'de>> de::Visitor<'de> for VariantSeed<C> {
+    type Value = u32;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("a variant index") }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        match name.parse() {
+            Ok(index) if index < C::LEN => Ok(index),
+            _ => Err(de::Error::custom(format_args!("unknown variant `{}`", name))),
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        if index < u64::from(C::LEN) {
+            Ok(index as u32)
+        } else {
+            Err(de::Error::invalid_value(de::Unexpected::Unsigned(index), &"a variant index in range"))
+        }
+    }
+}
+
+struct VariantVisitor<C>(PhantomData<C>);
+
+impl<'de, C: DeserializeVariant<'de>> de::Visitor<'de> for VariantVisitor<C> {
+    type Value = C;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("a coproduct") }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        let (index, variant) = data.variant_seed(VariantSeed::<C>(PhantomData))?;
+        C::deserialize_variant(index, variant)
+    }
+}
+
+impl Serialize for CoNil {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> { match *self {} }
+}
+
+impl<T: Serialize, R: SerializeVariant> Serialize for CoCons<T, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> { self.serialize_variant(0, serializer) }
+}
+
+impl<'de> Deserialize<'de> for CoNil {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum(ENUM, &[], VariantVisitor(PhantomData))
+    }
+}
+
+impl<'de, T: Deserialize<'de>, R: DeserializeVariant<'de>> Deserialize<'de> for CoCons<T, R> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_enum(ENUM, &[], VariantVisitor(PhantomData))
+    }
+}
@@ -1,4 +1,8 @@
-use typsy::{anon, anon::Transform, Anon};
+use typsy::{
+    anon,
+    anon::{IntoNamed, Transform},
+    Anon,
+};
 
 #[derive(Transform)]
 struct Vec3 {
@@ -41,3 +45,716 @@ fn test() {
         }
     )
 }
+
+#[test]
+fn tuple_round_trip() {
+    let TuplePoint(x, y, z) = TuplePoint::from_canon(TuplePoint(1.0, -2, 3).into_canon());
+    assert_eq!((x, y, z), (1.0, -2, 3));
+}
+
+#[test]
+fn field_names() {
+    use typsy::{anon::FieldName, field};
+
+    let mut name = String::new();
+    <field!(value)>::write_name(&mut name).unwrap();
+    assert_eq!(name, "value");
+    assert!(<field!(value)>::matches("value"));
+    assert!(!<field!(value)>::matches("val"));
+
+    let point = anon!(x = 1.0);
+    assert_eq!(point.value.name().to_string(), "x");
+
+    // names are split into chunks, so names that share a chunk must still differ
+    let record = anon!(abcd = 1, abcde = 2, abc = 3, position = 4);
+    assert_eq!(record.rest.value.name().to_string(), "abcde");
+    assert!(<field!(position)>::matches("position"));
+    assert_eq!(<field!(größe_2)>::NAME, "größe_2");
+    assert_eq!(
+        (*typsy::get!(record.abcd), *typsy::get!(record.abcde), *typsy::get!(record.abc)),
+        (1, 2, 3)
+    );
+}
+
+type Nested = Anon!(x: u8, inner: Anon!(y: String));
+
+fn inner_y(record: &Nested) -> &str { typsy::get!(record.inner.y) }
+
+fn increment_x(record: &mut Nested) { *typsy::get_mut!(record.x) += 1 }
+
+#[test]
+fn borrowed_fields() {
+    let mut record = anon!(x = 1, inner = anon!(y = "y".to_string()));
+    increment_x(&mut record);
+    assert_eq!(*typsy::get!(record.x), 2);
+    assert_eq!(inner_y(&record), "y");
+
+    let borrowed = &mut record;
+    typsy::get_mut!(borrowed.inner.y).push('z');
+    assert_eq!((*typsy::get!(borrowed.x), typsy::get!((&*borrowed).inner.y).as_str()), (2, "yz"));
+}
+
+#[test]
+fn debug() {
+    use typsy::hlist;
+
+    assert_eq!(format!("{:?}", anon!(x = 1.0, y = 2.0)), "{ x: 1.0, y: 2.0 }");
+    assert_eq!(format!("{:#?}", anon!(x = 1.0)), "{\n    x: 1.0,\n}");
+    assert_eq!(format!("{:?}", hlist!(anon::Named::<u8, typsy::field!(x)>::new(1), anon::Unnamed(2))), "{ x: 1, 1: 2 }");
+    assert_eq!(format!("{:?}", hlist!(anon::Unnamed(1), anon::Named::<u8, typsy::field!(y)>::new(2))), "{ 0: 1, y: 2 }");
+    assert_eq!(format!("{:?}", anon!(1, "a")), r#"[1, "a"]"#);
+    assert_eq!(format!("{:?}", hlist!(1, "a", 2.0)), r#"[1, "a", 2.0]"#);
+    assert_eq!(format!("{:?}", hlist!()), "[]");
+}
+
+mod v1 {
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub enum Shape {
+        Circle { radius: f32 },
+        Rect(f32, f32),
+        Empty,
+    }
+}
+
+mod v2 {
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub enum Shape {
+        Empty,
+        Rect(f32, f32),
+        Circle { radius: f32 },
+    }
+}
+
+#[derive(Transform, Debug, PartialEq)]
+enum Event {
+    Moved { y: f32, x: f32 },
+    Closed,
+}
+
+#[derive(Transform, Debug, PartialEq)]
+enum OtherEvent {
+    Closed,
+    Moved { x: f32, y: f32 },
+}
+
+#[test]
+fn enums() {
+    use v1::Shape as Old;
+    use v2::Shape as New;
+
+    assert_eq!(Old::Circle { radius: 1.0 }.transform::<New, _>(), New::Circle { radius: 1.0 });
+    assert_eq!(Old::Rect(1.0, 2.0).transform::<New, _>(), New::Rect(1.0, 2.0));
+    assert_eq!(New::Empty.transform::<Old, _>(), Old::Empty);
+
+    assert_eq!(Event::Moved { x: 1.0, y: 2.0 }.deep_transform::<OtherEvent, _>(), OtherEvent::Moved {
+        x: 1.0,
+        y: 2.0
+    });
+    assert_eq!(Event::Closed.deep_transform::<OtherEvent, _>(), OtherEvent::Closed);
+
+    // named variants print like tuple variants, inside the usual coproduct wrappers
+    assert_eq!(format!("{:?}", Event::Moved { x: 1.0, y: 2.0 }.into_canon()), "Value(Moved({ y: 2.0, x: 1.0 }))");
+    assert_eq!(format!("{:?}", Event::Closed.into_canon()), "Rest(Value(Closed([])))");
+
+    let _: fn(typsy::coprod::CoNil) -> Never = Never::from_canon;
+    let _: fn(Never) -> typsy::coprod::CoNil = Never::into_canon;
+}
+
+// an uninhabited enum's canon is `CoNil`, which can still be converted
+#[derive(Transform)]
+enum Never {}
+
+#[derive(Transform, Debug, PartialEq)]
+struct Marker;
+
+#[test]
+fn unit_struct() {
+    let canon: typsy::hlist::Nil = Marker.into_canon();
+    assert_eq!(Marker::from_canon(canon), Marker);
+    assert_eq!(anon!().into_named::<Marker, _>(), Marker);
+}
+
+#[derive(Transform, Debug, PartialEq)]
+struct Position {
+    pub x: f32,
+    pub y: f32,
+}
+
+fn default_label() -> &'static str { "none" }
+
+#[derive(Transform, Debug, PartialEq)]
+struct Entity {
+    #[typsy(rename = "id")]
+    pub key: u32,
+    #[typsy(flatten)]
+    pub position: Position,
+    #[typsy(skip)]
+    pub cache: Vec<u32>,
+    #[typsy(skip, default = "default_label")]
+    pub label: &'static str,
+}
+
+#[test]
+fn field_attributes() {
+    let entity = Entity {
+        key: 1,
+        position: Position { x: 2.0, y: 3.0 },
+        cache: vec![4],
+        label: "entity",
+    };
+
+    let canon: Anon!(id: u32, x: f32, y: f32) = entity.into_canon();
+    assert_eq!(canon, anon!(id = 1, x = 2.0, y = 3.0));
+
+    assert_eq!(anon!(y = 3.0, x = 2.0, id = 1).into_named::<Entity, _>(), Entity {
+        key: 1,
+        position: Position { x: 2.0, y: 3.0 },
+        cache: Vec::new(),
+        label: "none",
+    });
+}
+
+#[derive(Transform, Debug, PartialEq)]
+struct Token {
+    #[typsy(rename = "type")]
+    pub kind: u8,
+}
+
+#[test]
+fn rename_keyword() {
+    let canon: Anon!(r#type: u8) = Token { kind: 1 }.into_canon();
+    assert_eq!(canon, anon!(r#type = 1));
+    assert_eq!(anon!(r#type = 2).into_named::<Token, _>(), Token { kind: 2 });
+}
+
+mod private {
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Debug, PartialEq)]
+    #[typsy(allow_private, validate = "Range::validate", error = "&'static str")]
+    pub struct Range {
+        start: u32,
+        end: u32,
+    }
+
+    impl Range {
+        pub fn new(start: u32, end: u32) -> Self { Self { start, end } }
+
+        fn validate(self) -> Result<Self, &'static str> {
+            if self.start <= self.end {
+                Ok(self)
+            } else {
+                Err("start must not be after end")
+            }
+        }
+    }
+}
+
+#[test]
+fn private_fields() {
+    use private::Range;
+
+    let range = Range::new(1, 2);
+    let canon: Anon!(start: &u32, end: &u32) = typsy::as_ref::AsRef::as_ref(&range);
+    assert_eq!(canon, anon!(start = &1, end = &2));
+
+    // `validate` leaves out `Transform`, so a bad range is an `Err` rather than a panic
+    assert_eq!(
+        anon!(end = 1, start = 2).try_transform::<Range, _>(),
+        Err("start must not be after end")
+    );
+}
+
+struct Even(u32);
+
+impl anon::TryDeepTransformFrom<u32, ()> for Even {
+    type Error = u32;
+
+    fn try_deep_transform_from(value: u32) -> Result<Self, u32> {
+        if value.is_multiple_of(2) {
+            Ok(Even(value))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[derive(Transform)]
+struct Sample {
+    pub range: private::Range,
+    pub count: Even,
+}
+
+#[test]
+fn try_transform() {
+    use typsy::coprod::CoCons;
+
+    assert_eq!(anon!(end = 2, start = 1).try_transform::<private::Range, _>(), Ok(private::Range::new(1, 2)));
+    assert_eq!(
+        anon!(end = 1, start = 2).try_transform::<private::Range, _>(),
+        Err("start must not be after end")
+    );
+
+    let sample = anon!(count = 4u32, range = anon!(start = 1u32, end = 2u32)).try_deep_transform::<Sample, _>();
+    assert_eq!(sample.ok().map(|sample| (sample.range, sample.count.0)), Some((private::Range::new(1, 2), 4)));
+
+    let error = anon!(count = 3u32, range = anon!(start = 1u32, end = 2u32))
+        .try_deep_transform::<Sample, _>()
+        .err()
+        .unwrap();
+    assert!(matches!(error, CoCons::Rest(CoCons::Value(ref count)) if count.0 == 3));
+
+    let error = anon!(count = 4u32, range = anon!(start = 2u32, end = 1u32))
+        .try_deep_transform::<Sample, _>()
+        .err()
+        .unwrap();
+    assert!(matches!(error, CoCons::Value(ref range) if range.0 == CoCons::Value("start must not be after end")));
+
+    let pair: Result<(Even, Even), _> = anon::TryDeepTransform::try_deep_transform((2u32, 3u32));
+    assert_eq!(pair.err(), Some(CoCons::Rest(CoCons::Value(3))));
+    let array: Result<[Even; 3], _> = anon::TryDeepTransform::try_deep_transform([2u32, 5, 7]);
+    assert_eq!(array.err(), Some(5));
+}
+
+mod api {
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct UserV1 {
+        pub name: &'static str,
+        pub age: u32,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct UserV2 {
+        pub age: u32,
+        pub email: Option<&'static str>,
+        pub name: &'static str,
+        pub tags: Vec<&'static str>,
+    }
+}
+
+#[test]
+fn defaults_and_lossy() {
+    use api::{UserV1, UserV2};
+
+    let v1 = UserV1 { name: "ann", age: 30 };
+    assert_eq!(v1.transform_with_defaults::<UserV2>(), UserV2 {
+        age: 30,
+        email: None,
+        name: "ann",
+        tags: Vec::new(),
+    });
+
+    let v2 = UserV2 {
+        age: 30,
+        email: Some("ann@example.com"),
+        name: "ann",
+        tags: vec!["admin"],
+    };
+    assert_eq!(v2.transform_lossy::<UserV1, _>(), UserV1 { name: "ann", age: 30 });
+
+    assert_eq!(anon!(x = 1).transform_with_defaults::<Anon!(y: i32, x: i32)>(), anon!(y = 0, x = 1));
+}
+
+#[derive(Transform, Debug, PartialEq)]
+struct Config {
+    pub host: &'static str,
+    pub port: u16,
+    pub verbose: bool,
+}
+
+#[test]
+fn merge_and_update() {
+    use typsy::anon::Merge;
+
+    let merged = anon!(a = 1, b = "b").merge(anon!(b = 2.0, c = 'c'));
+    assert_eq!(merged, anon!(a = 1, b = 2.0, c = 'c'));
+
+    let config = Config {
+        host: "localhost",
+        port: 80,
+        verbose: false,
+    };
+    assert_eq!(config.update(anon!(port = 8080, verbose = true)), Config {
+        host: "localhost",
+        port: 8080,
+        verbose: true,
+    });
+}
+
+#[test]
+fn insert_and_replace_fields() {
+    use typsy::{anon::Access, field};
+
+    let record = anon!(x = 1).insert_field::<field!(y), _>("y");
+    assert_eq!(record, anon!(y = "y", x = 1));
+
+    let (old, record) = record.replace_field::<field!(x), _>(1.5);
+    assert_eq!(old, 1);
+    assert_eq!(record, anon!(y = "y", x = 1.5));
+}
+
+#[derive(Transform, Debug, PartialEq)]
+struct Identifiers {
+    pub snake_case: u8,
+    pub point3d: u8,
+    pub r#type: u8,
+    pub größe: u8,
+    pub _12: u8,
+}
+
+#[test]
+fn identifier_names() {
+    use typsy::{anon::FieldName, field};
+
+    let mut name = String::new();
+    <field!(größe_2)>::write_name(&mut name).unwrap();
+    assert_eq!(name, "größe_2");
+    assert!(<field!(r#type)>::matches("type"));
+    assert!(<field!(type)>::matches("type"));
+
+    let record = anon!(r#type = 3, snake_case = 1, _12 = 5, größe = 4, point3d = 2);
+    assert_eq!(format!("{:?}", record), "{ type: 3, snake_case: 1, _12: 5, größe: 4, point3d: 2 }");
+    assert_eq!(*typsy::get!(record.größe), 4);
+    assert_eq!(record.transform::<Identifiers, _>(), Identifiers {
+        snake_case: 1,
+        point3d: 2,
+        r#type: 3,
+        größe: 4,
+        _12: 5,
+    });
+}
+
+mod containers {
+    use std::{
+        collections::{BTreeMap, VecDeque},
+        rc::Rc,
+        sync::Arc,
+    };
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Clone, Debug, PartialEq)]
+    pub struct Inner {
+        pub a: u8,
+        pub b: u8,
+    }
+
+    #[derive(Transform, Clone, Debug, PartialEq)]
+    pub struct InnerRev {
+        pub b: u8,
+        pub a: u8,
+    }
+
+    macro_rules! outer {
+        ($name:ident, $inner:ident) => {
+            #[derive(Transform, Debug, PartialEq)]
+            pub struct $name {
+                pub option: Option<$inner>,
+                pub boxed: Box<$inner>,
+                pub array: [$inner; 2],
+                pub tuple: ($inner, u8),
+                pub map: BTreeMap<&'static str, $inner>,
+                pub deque: VecDeque<$inner>,
+                pub rc: Rc<$inner>,
+                pub arc: Arc<$inner>,
+                pub string: String,
+                pub str: &'static str,
+            }
+        };
+    }
+
+    outer!(Outer, Inner);
+    outer!(OuterRev, InnerRev);
+}
+
+#[test]
+fn deep_transform_containers() {
+    use containers::{Inner, InnerRev, Outer, OuterRev};
+
+    let inner = |a| Inner { a, b: a + 1 };
+    let inner_rev = |a| InnerRev { b: a + 1, a };
+
+    let outer = Outer {
+        option: Some(inner(1)),
+        boxed: Box::new(inner(2)),
+        array: [inner(3), inner(4)],
+        tuple: (inner(5), 6),
+        map: vec![("x", inner(7))].into_iter().collect(),
+        deque: vec![inner(8)].into_iter().collect(),
+        rc: std::rc::Rc::new(inner(9)),
+        arc: std::sync::Arc::new(inner(10)),
+        string: "string".to_string(),
+        str: "str",
+    };
+
+    let expected = OuterRev {
+        option: Some(inner_rev(1)),
+        boxed: Box::new(inner_rev(2)),
+        array: [inner_rev(3), inner_rev(4)],
+        tuple: (inner_rev(5), 6),
+        map: vec![("x", inner_rev(7))].into_iter().collect(),
+        deque: vec![inner_rev(8)].into_iter().collect(),
+        rc: std::rc::Rc::new(inner_rev(9)),
+        arc: std::sync::Arc::new(inner_rev(10)),
+        string: "string".to_string(),
+        str: "str",
+    };
+
+    assert_eq!(outer.deep_transform::<OuterRev, _>(), expected);
+}
+
+mod schema {
+    use typsy::anon::Transform;
+
+    #[derive(Transform)]
+    pub struct ItemV1 {
+        pub id: u32,
+        pub name: String,
+    }
+
+    #[derive(Transform)]
+    pub struct OrderV1 {
+        pub items: Vec<ItemV1>,
+        pub total: f32,
+        pub note: Option<String>,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct ItemV2 {
+        pub name: Box<str>,
+        pub id: u64,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct OrderV2 {
+        pub note: Option<Box<str>>,
+        pub total: f64,
+        pub items: Vec<ItemV2>,
+    }
+}
+
+#[test]
+fn deep_convert() {
+    use schema::*;
+
+    let order = OrderV1 {
+        items: vec![ItemV1 {
+            id: 7,
+            name: "apple".to_string(),
+        }],
+        total: 1.5,
+        note: None,
+    };
+
+    assert_eq!(order.deep_convert::<OrderV2, _>(), OrderV2 {
+        note: None,
+        total: 1.5,
+        items: vec![ItemV2 {
+            name: "apple".into(),
+            id: 7,
+        }],
+    });
+
+    // other types become leaves by converting from them
+    #[derive(Debug, PartialEq)]
+    struct Cents(u64);
+
+    impl anon::DeepTransformFrom<u32, (), anon::ConvertLeaves> for Cents {
+        fn deep_transform_from(value: u32) -> Self { Cents(value.into()) }
+    }
+
+    let price: Anon!(price: Cents) = anon::DeepConvert::deep_convert(anon!(price = 150u32));
+    assert_eq!(price, anon!(price = Cents(150)));
+}
+
+#[derive(Transform)]
+struct Segment(pub Point, pub u8);
+
+#[derive(Transform)]
+struct SegmentRev(#[typsy(position = 1)] pub u8, #[typsy(position = 0)] pub Extra2);
+
+#[derive(Transform)]
+struct Extra2 {
+    pub w: f32,
+    pub z: Extra,
+    pub y: f32,
+}
+
+#[derive(Transform)]
+struct Labelled {
+    #[typsy(position = 1)]
+    pub label: u8,
+    #[typsy(position = 0)]
+    pub point: Extra2,
+}
+
+#[test]
+fn positional() {
+    let segment = Segment(
+        Point {
+            y: 1.0,
+            w: 2.0,
+            z: anon!(value = 3.0),
+        },
+        4,
+    );
+
+    let SegmentRev(label, point) = segment.deep_transform::<SegmentRev, _>();
+    assert_eq!(label, 4);
+    assert_eq!((point.w, point.y, point.z.value), (2.0, 1.0, 3.0));
+
+    let labelled = SegmentRev(5, point).transform::<Labelled, _>();
+    assert_eq!(labelled.label, 5);
+    assert_eq!(labelled.point.w, 2.0);
+}
+
+#[derive(Transform)]
+struct Vec3View<'a> {
+    pub y: &'a f32,
+    pub z: &'a Extra,
+}
+
+#[test]
+fn deep_view() {
+    use typsy::anon::DeepView;
+
+    let vec = Vec3 {
+        w: 0.0,
+        x: 1.0,
+        y: 2.0,
+        z: Extra { value: 3.0 },
+    };
+
+    let view = vec.deep_view::<Anon!(x: &f32, y: &f32), _>();
+    assert_eq!(view, anon!(x = &1.0, y = &2.0));
+    assert!(std::ptr::eq(view.value.0, &vec.x));
+
+    let nested = vec.deep_view::<Anon!(z: Anon!(value: &f32), w: &f32), _>();
+    assert_eq!(nested, anon!(z = anon!(value = &3.0), w = &0.0));
+
+    let view = vec.deep_view::<Vec3View, _>();
+    assert_eq!((*view.y, view.z.value), (2.0, 3.0));
+
+    let record = anon!(a = 1, b = "b");
+    assert_eq!(record.deep_view::<Anon!(b: &&str), _>(), anon!(b = &"b"));
+
+    let mut vec = vec;
+    let typsy::hlist_pat!(w, @_) = typsy::as_ref::AsRef::as_mut(&mut vec);
+    *w.0 = 5.0;
+    assert_eq!(vec.w, 5.0);
+}
+
+mod generic {
+    use std::fmt::Debug;
+    use typsy::anon::Transform;
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct Wrapper<'a, T: Clone> {
+        pub inner: &'a T,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct Pair<A, B = u8>
+    where
+        A: Debug,
+        Self: Sized,
+    {
+        pub first: A,
+        pub second: B,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct Swapped<A: Debug, B> {
+        pub second: B,
+        pub first: A,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct List<T> {
+        pub value: T,
+        pub next: Option<Box<Self>>,
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub enum Either<'a, L: Clone, R> {
+        Left(&'a L),
+        Right { value: R },
+    }
+
+    #[derive(Transform)]
+    pub struct Defaulted<T>
+    where
+        Self: Default,
+    {
+        pub value: T,
+    }
+
+    impl<T: Default> Default for Defaulted<T> {
+        fn default() -> Self { Self { value: T::default() } }
+    }
+
+    #[derive(Transform, Debug, PartialEq)]
+    pub struct Array<T, const N: usize> {
+        pub items: [T; N],
+    }
+}
+
+#[test]
+fn generics() {
+    use generic::*;
+
+    let value = String::from("inner");
+    let wrapper = Wrapper { inner: &value };
+    assert_eq!(wrapper.transform::<Wrapper<String>, _>(), Wrapper { inner: &value });
+    assert_eq!(
+        Wrapper::from_canon(anon!(inner = &value)).transform::<Anon!(inner: &String), _>(),
+        anon!(inner = &value)
+    );
+
+    let pair = Pair { first: "a", second: 1 };
+    assert_eq!(pair.transform::<Swapped<&str, u8>, _>(), Swapped { second: 1, first: "a" });
+
+    let list = List {
+        value: 1,
+        next: Some(Box::new(List { value: 2, next: None })),
+    };
+    assert_eq!(list.transform::<List<i32>, _>(), List {
+        value: 1,
+        next: Some(Box::new(List { value: 2, next: None })),
+    });
+
+    let either = Either::<u8, &str>::Left(&3);
+    assert_eq!(either.transform::<Either<u8, &str>, _>(), Either::Left(&3));
+
+    use typsy::anon::DeepView;
+    let record = anon!(defaulted = Defaulted { value: 1 });
+    assert_eq!(
+        record.deep_view::<Anon!(defaulted: Anon!(value: &i32)), _>(),
+        anon!(defaulted = anon!(value = &1))
+    );
+
+    let array = Array { items: [1, 2] };
+    assert_eq!(array.deep_transform::<Array<i32, 2>, _>(), Array { items: [1, 2] });
+}
+
+mod facade {
+    pub use typsy as reexported;
+}
+
+#[derive(facade::reexported::anon::Transform, Debug, PartialEq)]
+#[typsy(crate = "crate::facade::reexported")]
+struct Facade {
+    pub value: u8,
+    #[typsy(skip)]
+    pub skipped: u8,
+}
+
+#[test]
+fn crate_path() {
+    assert_eq!(anon!(value = 1).into_named::<Facade, _>(), Facade { value: 1, skipped: 0 });
+}
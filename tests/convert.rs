@@ -0,0 +1,87 @@
+use typsy::{
+    anon,
+    anon::{Transform, TryTransform},
+    call,
+    convert::{Convert, Generic},
+    fold::Fold,
+    hlist,
+    map::Map,
+    zip::Zip,
+};
+
+#[derive(Generic, Debug, PartialEq)]
+struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub label: &'static str,
+}
+
+#[derive(Generic, Debug, PartialEq)]
+struct Pair<T>(pub T, pub T);
+
+#[derive(Generic, Debug, PartialEq)]
+struct Unit;
+
+#[derive(Generic, Debug, PartialEq)]
+#[typsy(allow_private)]
+struct Private {
+    value: u8,
+}
+
+// `Generic` shares `#[typsy(...)]` with `Transform`, and leaves `validate` and `error` to it
+#[derive(Transform, Generic, Debug, PartialEq)]
+#[typsy(validate = "NonZero::validate", error = "&'static str")]
+struct NonZero {
+    pub value: u32,
+}
+
+impl NonZero {
+    fn validate(self) -> Result<Self, &'static str> { if self.value != 0 { Ok(self) } else { Err("zero") } }
+}
+
+struct Double;
+struct Total;
+struct Max;
+
+call! {
+    fn(&self: Double, value: i32) -> i32 { value * 2 }
+    fn(&self: Double, value: &'static str) -> &'static str { value }
+    fn(&self: Total, total: usize, value: i32) -> usize { total + value as usize }
+    fn(&self: Total, total: usize, value: &'static str) -> usize { total + value.len() }
+    fn(&self: Max, pair: (i32, i32)) -> i32 { pair.0.max(pair.1) }
+    fn(&self: Max, pair: (&'static str, &'static str)) -> &'static str { pair.0.max(pair.1) }
+}
+
+#[test]
+fn round_trip() {
+    let point = Point { x: 1, y: 2, label: "a" };
+    assert_eq!(point.into_hlist(), hlist!(1, 2, "a"));
+    assert_eq!(Point::from_hlist(hlist!(1, 2, "a")), Point { x: 1, y: 2, label: "a" });
+
+    assert_eq!(Pair(1, 2).into_hlist(), hlist!(1, 2));
+    assert_eq!(Pair::from_hlist(hlist!("a", "b")), Pair("a", "b"));
+
+    assert_eq!(Unit.into_hlist(), hlist!());
+    assert_eq!(Unit::from_hlist(hlist!()), Unit);
+
+    assert_eq!(Private::from_hlist(Private { value: 3 }.into_hlist()), Private { value: 3 });
+
+    assert_eq!(NonZero::from_hlist(NonZero { value: 2 }.into_hlist()), NonZero { value: 2 });
+    assert_eq!(NonZero::try_from_canon(anon!(value = 0)), Err("zero"));
+    assert_eq!(anon!(value = 4u32).try_transform::<NonZero, _>(), Ok(NonZero { value: 4 }));
+}
+
+#[test]
+fn combinators() {
+    // the tag lists default to `()`, naming the traits picks the untagged impls
+    let point = Point { x: 1, y: 2, label: "a" };
+    assert_eq!(Point::from_hlist(Map::<Double>::map(point.into_hlist(), Double)), Point { x: 2, y: 4, label: "a" });
+
+    let point = Point { x: 1, y: 2, label: "abc" };
+    assert_eq!(Fold::<usize, Total>::fold(point.into_hlist(), 0, Total), 6);
+
+    let left = Point { x: 1, y: 5, label: "a" };
+    let right = Point { x: 3, y: 4, label: "b" };
+    let max = Map::<Max>::map(left.into_hlist().zip(right.into_hlist()), Max);
+    assert_eq!(Point::from_hlist(max), Point { x: 3, y: 5, label: "b" });
+}
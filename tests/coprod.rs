@@ -0,0 +1,55 @@
+use typsy::{
+    coprod::{CoCons, Embed},
+    peano::*,
+    CoProd, TyList,
+};
+
+#[derive(Debug, PartialEq)]
+struct IoError;
+#[derive(Debug, PartialEq)]
+struct ParseError;
+
+type Error = CoProd!(IoError, ParseError, IoError);
+
+#[test]
+fn embed_duplicates() {
+    let read: CoProd!(IoError) = CoCons::Value(IoError);
+    let write: CoProd!(ParseError, IoError) = CoCons::Rest(CoCons::Value(IoError));
+
+    let read: Error = read.embed::<_, TyList!(P0)>();
+    let write: Error = write.embed::<_, TyList!(P1, P2)>();
+
+    assert_eq!(read, CoCons::Value(IoError));
+    assert_eq!(write, CoCons::Rest(CoCons::Rest(CoCons::Value(IoError))));
+}
+
+#[test]
+fn as_dyn() {
+    use core::fmt::Debug;
+    use typsy::coprod::CoProdAs;
+
+    let mut error: Error = CoCons::Rest(CoCons::Value(ParseError));
+    let debug: &dyn Debug = error.as_dyn();
+    assert_eq!(format!("{:?}", debug), "ParseError");
+
+    let any: &mut dyn core::any::Any = error.as_dyn_mut();
+    assert!(any.is::<ParseError>());
+
+    let boxed: Box<dyn Debug> = error.into_dyn();
+    assert_eq!(format!("{:?}", boxed), "ParseError");
+}
+
+#[test]
+fn variant() {
+    use core::any::TypeId;
+    use typsy::coprod::Variant;
+
+    let error: Error = CoCons::Rest(CoCons::Value(ParseError));
+    assert_eq!(error.variant_index(), 1);
+    assert!(error.variant_type_name().ends_with("ParseError"));
+    assert_eq!(error.variant_type_id(), TypeId::of::<ParseError>());
+
+    let error: Error = CoCons::Rest(CoCons::Rest(CoCons::Value(IoError)));
+    assert_eq!(error.variant_index(), 2);
+    assert_eq!(error.variant_type_id(), TypeId::of::<IoError>());
+}
@@ -0,0 +1,83 @@
+use typsy::{
+    convert::Generic,
+    ops::{Semigroup, TypsyAdd, TypsyDefault, TypsyMerge},
+};
+
+#[derive(Generic, TypsyAdd, TypsyDefault, TypsyMerge, Debug, PartialEq)]
+struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Generic, TypsyAdd, TypsyDefault, Debug, PartialEq)]
+struct Wrapper<T>(pub T);
+
+// the ops derives share `#[typsy(...)]` with `Generic`, and leave its options to it
+#[derive(Generic, TypsyAdd, Debug, PartialEq)]
+#[typsy(allow_private)]
+struct Private {
+    x: u32,
+}
+
+#[derive(Generic, TypsyDefault, TypsyMerge, Debug, PartialEq)]
+struct Stats {
+    pub count: u32,
+    pub names: Vec<String>,
+    pub score: Option<u8>,
+}
+
+#[derive(Generic, TypsyMerge, Debug, PartialEq)]
+struct Nested {
+    pub stats: Stats,
+    pub total: u64,
+}
+
+#[test]
+fn add() {
+    assert_eq!(Vec2 { x: 1.0, y: 2.0 } + Vec2 { x: 3.0, y: 4.0 }, Vec2 { x: 4.0, y: 6.0 });
+    assert_eq!(Wrapper(1) + Wrapper(2), Wrapper(3));
+    assert_eq!(Wrapper(Vec2 { x: 1.0, y: 0.0 }) + Wrapper(Vec2 { x: 0.0, y: 1.0 }), Wrapper(Vec2 { x: 1.0, y: 1.0 }));
+    assert_eq!(Private { x: 1 } + Private { x: 2 }, Private { x: 3 });
+}
+
+#[test]
+fn default() {
+    assert_eq!(Vec2::default(), Vec2 { x: 0.0, y: 0.0 });
+    assert_eq!(Wrapper::<u8>::default(), Wrapper(0));
+    assert_eq!(
+        Stats::default(),
+        Stats {
+            count: 0,
+            names: Vec::new(),
+            score: None,
+        }
+    );
+}
+
+#[test]
+fn merge() {
+    assert_eq!(Vec2 { x: 1.0, y: 2.0 }.combine(Vec2 { x: 3.0, y: 4.0 }), Vec2 { x: 4.0, y: 6.0 });
+
+    let left = Stats {
+        count: 1,
+        names: vec!["a".to_string()],
+        score: None,
+    };
+    let right = Stats {
+        count: 2,
+        names: vec!["b".to_string()],
+        score: Some(3),
+    };
+    let stats = Stats {
+        count: 3,
+        names: vec!["a".to_string(), "b".to_string()],
+        score: Some(3),
+    };
+    assert_eq!(left.combine(right), stats);
+
+    let nested = Nested {
+        stats: Stats::default(),
+        total: 1,
+    };
+    assert_eq!(nested.combine(Nested { stats, total: 2 }).total, 3);
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "serde")]
+
+use typsy::{anon, coprod::CoCons, hlist, Anon, CoProd, HList};
+
+#[test]
+fn hlist() {
+    let list = hlist!(1, "a", 2.5);
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, r#"[1,"a",2.5]"#);
+
+    let back: HList!(i32, String, f64) = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, hlist!(1, "a".to_string(), 2.5));
+
+    assert!(serde_json::from_str::<HList!(i32, String, f64)>("[1, \"a\"]").is_err());
+}
+
+#[test]
+fn anon() {
+    let point = anon!(x = 1.0, y = 2.0);
+    let json = serde_json::to_string(&point).unwrap();
+    assert_eq!(json, r#"{"x":1.0,"y":2.0}"#);
+
+    let back: Anon!(x: f32, y: f32) = serde_json::from_str(r#"{"y":2.0,"extra":null,"x":1.0}"#).unwrap();
+    assert_eq!(back, point);
+
+    let unnamed: Anon!(f32, bool) = serde_json::from_str("[1.0,true]").unwrap();
+    assert_eq!(unnamed, anon!(1.0, true));
+
+    let error = serde_json::from_str::<Anon!(x: f32, y: f32)>(r#"{"x":1.0}"#).unwrap_err();
+    assert!(error.to_string().contains("missing field `y`"));
+
+    let error = serde_json::from_str::<Anon!(x: f32, y: f32)>(r#"{"x":1.0,"x":1.0}"#).unwrap_err();
+    assert!(error.to_string().contains("duplicate field `x`"));
+}
+
+#[test]
+fn coprod() {
+    let event: CoProd!(u8, String, u8) = CoCons::Rest(CoCons::Rest(CoCons::Value(3)));
+    let json = serde_json::to_string(&event).unwrap();
+    assert_eq!(json, r#"{"2":3}"#);
+
+    let back: CoProd!(u8, String, u8) = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, event);
+
+    let error = serde_json::from_str::<CoProd!(u8, String, u8)>(r#"{"3":3}"#).unwrap_err();
+    assert!(error.to_string().contains("unknown variant `3`"));
+
+    type Wide = CoProd!(u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8, u8);
+    let last: Wide = serde_json::from_str(r#"{"11":4}"#).unwrap();
+    assert_eq!(serde_json::to_string(&last).unwrap(), r#"{"11":4}"#);
+}
+
+#[derive(typsy::anon::Transform, Debug, PartialEq)]
+enum Event {
+    Moved { x: i32, y: i32 },
+    Renamed(String),
+    Closed,
+}
+
+#[test]
+fn named_coprod() {
+    use typsy::anon::Transform;
+
+    let round_trip = |event: Event, expected: &str| {
+        let json = serde_json::to_string(&event.into_canon()).unwrap();
+        assert_eq!(json, expected);
+        Event::from_canon(serde_json::from_str(&json).unwrap())
+    };
+
+    assert_eq!(round_trip(Event::Moved { x: 1, y: 2 }, r#"{"Moved":{"x":1,"y":2}}"#), Event::Moved {
+        x: 1,
+        y: 2
+    });
+    assert_eq!(
+        round_trip(Event::Renamed("a".to_string()), r#"{"Renamed":["a"]}"#),
+        Event::Renamed("a".to_string())
+    );
+    assert_eq!(round_trip(Event::Closed, r#"{"Closed":[]}"#), Event::Closed);
+
+    let error = serde_json::from_str::<<Event as Transform>::Canon>(r#"{"Opened":[]}"#).unwrap_err();
+    assert!(error.to_string().contains("unknown variant `Opened`"));
+}
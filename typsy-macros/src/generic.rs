@@ -0,0 +1,69 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::options::Options;
+
+/// structs map to an hlist of their fields, in declaration order and without names
+pub fn derive(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let syn::DeriveInput {
+        attrs,
+        data,
+        generics,
+        ident,
+        ..
+    } = input;
+
+    let options = Options::new(&attrs)?;
+    let krate = &options.krate;
+
+    let fields = match data {
+        syn::Data::Struct(data) => data.fields,
+        syn::Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span(),
+                "`Generic` can only be derived for structs",
+            ))
+        }
+        syn::Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "`Generic` can only be derived for structs",
+            ))
+        }
+    };
+
+    options.check_fields(&fields)?;
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    let types = fields.iter().map(|field| &field.ty);
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match field.ident {
+            Some(ref ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index {
+                index: index as u32,
+                span: field.span(),
+            }),
+        })
+        .collect::<Vec<_>>();
+    let bindings = (0..members.len())
+        .map(|index| quote::format_ident!("__typsy_{}", index))
+        .collect::<Vec<_>>();
+
+    Ok(quote!(
+        impl #impl_generics #krate::convert::Convert for #ident #type_generics #where_clause {
+            type HList = #krate::HList!(#(#types),*);
+
+            fn into_hlist(self) -> Self::HList {
+                let Self { #(#members: #bindings),* } = self;
+                #krate::hlist!(#(#bindings),*)
+            }
+
+            fn from_hlist(#krate::hlist_pat!(#(#bindings),*): Self::HList) -> Self { Self { #(#members: #bindings),* } }
+        }
+    ))
+}
+
@@ -0,0 +1,107 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::options::Options;
+
+/// the parts shared by the field-wise derives, which all go through `convert::Convert`
+struct Derive {
+    options: Options,
+    ident: syn::Ident,
+    generics: syn::Generics,
+}
+
+impl Derive {
+    fn new(input: syn::DeriveInput) -> syn::Result<Self> {
+        Ok(Self {
+            options: Options::new(&input.attrs)?,
+            ident: input.ident,
+            generics: input.generics,
+        })
+    }
+
+    /// implements `trait_` with `body`, where `Self: Convert` and the `bounds` on its hlist hold
+    fn implement(&self, trait_: TokenStream, bounds: &[TokenStream], body: TokenStream) -> TokenStream {
+        let krate = &self.options.krate;
+        let ident = &self.ident;
+        let mut generics = self.generics.clone();
+        let where_clause = generics.make_where_clause();
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(Self: #krate::convert::Convert));
+        for bound in bounds {
+            where_clause.predicates.push(syn::parse_quote!(#bound));
+        }
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        quote!(
+            impl #impl_generics #trait_ for #ident #type_generics #where_clause {
+                #body
+            }
+        )
+    }
+}
+
+/// `Add` zips the hlists of both sides, and maps `ops::Add` over the pairs
+pub fn add(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let derive = Derive::new(input)?;
+    let krate = &derive.options.krate;
+    let hlist = quote!(<Self as #krate::convert::Convert>::HList);
+    let zipped = quote!(#krate::zip::Zipped<#hlist, #hlist>);
+
+    Ok(derive.implement(
+        quote!(#krate::core::ops::Add),
+        &[
+            quote!(#hlist: #krate::zip::Zip<#hlist>),
+            quote!(#zipped: #krate::map::Map<#krate::ops::Add, Output = #hlist>),
+        ],
+        quote!(
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                let zipped = #krate::zip::Zip::zip(
+                    #krate::convert::Convert::into_hlist(self),
+                    #krate::convert::Convert::into_hlist(other),
+                );
+                #krate::convert::Convert::from_hlist(#krate::map::Map::<#krate::ops::Add>::map(zipped, #krate::ops::Add))
+            }
+        ),
+    ))
+}
+
+/// `Default` builds the hlist from the default of each field
+pub fn default(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let derive = Derive::new(input)?;
+    let krate = &derive.options.krate;
+    let hlist = quote!(<Self as #krate::convert::Convert>::HList);
+
+    Ok(derive.implement(
+        quote!(#krate::core::default::Default),
+        &[quote!(#hlist: #krate::core::default::Default)],
+        quote!(
+            fn default() -> Self {
+                #krate::convert::Convert::from_hlist(#krate::core::default::Default::default())
+            }
+        ),
+    ))
+}
+
+/// `Semigroup` combines the hlists of both sides element-wise
+pub fn merge(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let derive = Derive::new(input)?;
+    let krate = &derive.options.krate;
+    let hlist = quote!(<Self as #krate::convert::Convert>::HList);
+
+    Ok(derive.implement(
+        quote!(#krate::ops::Semigroup),
+        &[quote!(#hlist: #krate::ops::Semigroup)],
+        quote!(
+            fn combine(self, other: Self) -> Self {
+                #krate::convert::Convert::from_hlist(#krate::ops::Semigroup::combine(
+                    #krate::convert::Convert::into_hlist(self),
+                    #krate::convert::Convert::into_hlist(other),
+                ))
+            }
+        ),
+    ))
+}
@@ -0,0 +1,104 @@
+use syn::spanned::Spanned;
+
+/// Options set on the type with `#[typsy(...)]`, shared by all derives, each of which uses the ones it needs
+pub struct Options {
+    /// the path to `typsy`, set with `crate = "path"` or found in `Cargo.toml`
+    pub krate: syn::Path,
+    /// allows non-`pub` fields, which are then exposed through the canon
+    pub allow_private: bool,
+    /// called on the value built by `from_canon`, `fn(Self) -> Result<Self, Error>`
+    pub validate: Option<syn::ExprPath>,
+    /// the error returned by `validate`, and the `TryTransform::Error`
+    pub error: Option<syn::Type>,
+}
+
+impl Options {
+    pub fn new(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self {
+            krate: crate_path(),
+            allow_private: false,
+            validate: None,
+            error: None,
+        };
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("typsy")) {
+            let list = match attr.parse_meta()? {
+                syn::Meta::List(list) => list,
+                meta => return Err(syn::Error::new(meta.span(), "expected `#[typsy(...)]`")),
+            };
+
+            for nested in list.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("allow_private") => {
+                        options.allow_private = true
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("validate") => options.validate = Some(lit.parse()?),
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("error") => options.error = Some(lit.parse()?),
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("crate") => options.krate = lit.parse()?,
+                    nested => return Err(syn::Error::new(nested.span(), "unknown `typsy` attribute")),
+                }
+            }
+        }
+
+        match (&options.validate, &options.error) {
+            (Some(validate), None) => Err(syn::Error::new(
+                validate.span(),
+                "`validate` requires `error`, the error type it returns",
+            )),
+            (None, Some(error)) => Err(syn::Error::new(error.span(), "`error` requires `validate`")),
+            _ => Ok(options),
+        }
+    }
+
+    /// checks that every field is `pub`, unless the type has `#[typsy(allow_private)]`
+    pub fn check_fields<'a>(&self, fields: impl IntoIterator<Item = &'a syn::Field>) -> syn::Result<()> {
+        let mut errors = None::<syn::Error>;
+
+        for field in fields {
+            match field.vis {
+                syn::Visibility::Public(_) => (),
+                _ if self.allow_private => (),
+                _ => {
+                    let span = field.ident.as_ref().map(syn::Ident::span).unwrap_or_else(|| field.ty.span());
+                    let error = syn::Error::new(
+                        span,
+                        "All fields must be `pub`, use `#[typsy(allow_private)]` to expose private fields",
+                    );
+                    match errors {
+                        Some(ref mut errors) => errors.combine(error),
+                        None => errors = Some(error),
+                    }
+                }
+            }
+        }
+
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
+
+/// the path to `typsy` in the crate being compiled, which may have renamed it in its `Cargo.toml`
+pub fn crate_path() -> syn::Path {
+    match proc_macro_crate::crate_name("typsy") {
+        Ok(proc_macro_crate::FoundCrate::Itself) => syn::parse_quote!(crate),
+        Ok(proc_macro_crate::FoundCrate::Name(name)) => {
+            let name = syn::Ident::new(&name, proc_macro2::Span::call_site());
+            syn::parse_quote!(::#name)
+        }
+        Err(_) => syn::parse_quote!(::typsy),
+    }
+}
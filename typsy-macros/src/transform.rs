@@ -0,0 +1,642 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+
+use crate::options::Options;
+
+/// How a field maps to the canon
+enum Kind {
+    /// maps to a field of the canon, `Named` if it has a `name` and `Unnamed` otherwise
+    Field { name: Option<syn::Ident> },
+    /// left out of the canon, and filled with `default` in `from_canon`
+    Skip { default: TokenStream },
+    /// splices the field's canon into the canon
+    Flatten,
+}
+
+struct Field {
+    member: syn::Member,
+    binding: syn::Ident,
+    ty: syn::Type,
+    kind: Kind,
+    /// the position of the field in the canon, set with `#[typsy(position = 0)]`
+    position: Option<usize>,
+}
+
+/// Whether a canon holds the fields themselves, or borrows them for `as_ref::AsRef`
+#[derive(Clone, Copy)]
+enum Mode {
+    Owned,
+    Ref,
+    Mut,
+}
+
+impl Mode {
+    /// the type of a field in the canon
+    fn field(self, ty: &syn::Type) -> TokenStream {
+        match self {
+            Mode::Owned => quote!(#ty),
+            Mode::Ref => quote!(&'__typsy #ty),
+            Mode::Mut => quote!(&'__typsy mut #ty),
+        }
+    }
+
+    /// the canon of a flattened field
+    fn flatten(self, krate: &syn::Path, ty: &syn::Type) -> TokenStream {
+        match self {
+            Mode::Owned => quote!(<#ty as #krate::anon::Transform>::Canon),
+            Mode::Ref => quote!(<#ty as #krate::as_ref::AsRef<'__typsy>>::Ref),
+            Mode::Mut => quote!(<#ty as #krate::as_ref::AsRef<'__typsy>>::RefMut),
+        }
+    }
+
+    /// converts a flattened field into its canon
+    fn into_flatten(self, krate: &syn::Path, binding: &syn::Ident) -> TokenStream {
+        match self {
+            Mode::Owned => quote!(#krate::anon::Transform::into_canon(#binding)),
+            Mode::Ref => quote!(#krate::as_ref::AsRef::as_ref(#binding)),
+            Mode::Mut => quote!(#krate::as_ref::AsRef::as_mut(#binding)),
+        }
+    }
+}
+
+/// The canon of a set of fields, and the pieces needed to convert to and from it
+struct Record {
+    fields: Vec<Field>,
+    krate: syn::Path,
+}
+
+impl Field {
+    fn new(field: &syn::Field, index: usize, krate: &syn::Path) -> syn::Result<Self> {
+        let mut rename = None;
+        let mut skip = false;
+        let mut default = None;
+        let mut flatten = false;
+        let mut position = None;
+
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("typsy")) {
+            let list = match attr.parse_meta()? {
+                syn::Meta::List(list) => list,
+                meta => return Err(syn::Error::new(meta.span(), "expected `#[typsy(...)]`")),
+            };
+
+            for nested in list.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => skip = true,
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("flatten") => flatten = true,
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("default") => {
+                        default = Some(quote!(#krate::core::default::Default::default()))
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("rename") => {
+                        // like `field!`, keywords are valid names, `rename = "type"` names the field `r#type`
+                        rename = Some(lit.parse_with(syn::ext::IdentExt::parse_any)?)
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Int(lit),
+                        ..
+                    })) if path.is_ident("position") => position = Some(lit.base10_parse::<usize>()?),
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                        path,
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    })) if path.is_ident("default") => {
+                        let path = lit.parse::<syn::ExprPath>()?;
+                        default = Some(quote!(#path()))
+                    }
+                    nested => return Err(syn::Error::new(nested.span(), "unknown `typsy` attribute")),
+                }
+            }
+        }
+
+        let span = field.span();
+        if position.is_some() {
+            match (&rename, skip, flatten) {
+                (Some(_), ..) => return Err(syn::Error::new(span, "cannot `rename` a positional field")),
+                (_, true, _) => return Err(syn::Error::new(span, "cannot `skip` a positional field")),
+                (_, _, true) => return Err(syn::Error::new(span, "cannot `flatten` a positional field")),
+                _ => (),
+            }
+        }
+
+        let kind = match (rename, skip, default, flatten) {
+            (_, false, Some(_), _) => return Err(syn::Error::new(span, "`default` requires `skip`")),
+            (Some(_), true, _, _) => return Err(syn::Error::new(span, "cannot `rename` a skipped field")),
+            (Some(_), _, _, true) => return Err(syn::Error::new(span, "cannot `rename` a flattened field")),
+            (_, true, _, true) => return Err(syn::Error::new(span, "cannot `skip` a flattened field")),
+            (Some(_), ..) if field.ident.is_none() => {
+                return Err(syn::Error::new(span, "cannot `rename` an unnamed field"))
+            }
+            (_, true, default, false) => Kind::Skip {
+                default: default.unwrap_or_else(|| quote!(#krate::core::default::Default::default())),
+            },
+            (_, false, None, true) => Kind::Flatten,
+            (_, false, None, false) if position.is_some() => Kind::Field { name: None },
+            (rename, false, None, false) => Kind::Field {
+                name: rename.or_else(|| field.ident.clone()),
+            },
+        };
+
+        let member = match field.ident {
+            Some(ref ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index {
+                index: index as u32,
+                span,
+            }),
+        };
+
+        // bindings are numbered, so that names like `_0` or `größe` don't trip style lints
+        let binding = quote::format_ident!("__typsy_{}", index, span = span);
+
+        Ok(Self {
+            member,
+            binding,
+            ty: field.ty.clone(),
+            kind,
+            position,
+        })
+    }
+}
+
+impl Record {
+    fn new(fields: &syn::Fields, krate: &syn::Path) -> syn::Result<Self> {
+        let mut fields = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| Field::new(field, index, krate))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        // with positions, the canon lists the fields by position instead of in declaration order
+        if fields.iter().any(|field| field.position.is_some()) {
+            let mut taken = vec![false; fields.iter().filter(|field| field.position.is_some()).count()];
+
+            for field in &fields {
+                let span = field.member.span();
+                match (field.position, &field.kind) {
+                    (_, Kind::Skip { .. }) => (),
+                    (None, _) => {
+                        return Err(syn::Error::new(
+                            span,
+                            "either every field or no field must have a `position`",
+                        ))
+                    }
+                    (Some(position), _) => match taken.get_mut(position) {
+                        Some(true) => return Err(syn::Error::new(span, "duplicate `position`")),
+                        Some(taken) => *taken = true,
+                        None => {
+                            return Err(syn::Error::new(
+                                span,
+                                format!("`position` must be less than the number of fields, {}", taken.len()),
+                            ))
+                        }
+                    },
+                }
+            }
+
+            fields.sort_by_key(|field| field.position.unwrap_or(usize::MAX));
+        }
+
+        Ok(Self {
+            fields,
+            krate: krate.clone(),
+        })
+    }
+
+    /// the canon type, along with the canon type of the fields after each field
+    fn canon(&self, mode: Mode) -> (TokenStream, Vec<TokenStream>) {
+        let krate = &self.krate;
+        let mut canon = quote!(#krate::hlist::Nil);
+        let mut tails = Vec::new();
+
+        for Field { ty, kind, .. } in self.fields.iter().rev() {
+            tails.push(canon.clone());
+            canon = match kind {
+                Kind::Field { name: Some(name) } => {
+                    let ty = mode.field(ty);
+                    quote!(#krate::hlist::Cons<#krate::anon::Named<#ty, #krate::field!(#name)>, #canon>)
+                }
+                Kind::Field { name: None } => {
+                    let ty = mode.field(ty);
+                    quote!(#krate::hlist::Cons<#krate::anon::Unnamed<#ty>, #canon>)
+                }
+                Kind::Skip { .. } => canon,
+                Kind::Flatten => {
+                    let flatten = mode.flatten(krate, ty);
+                    quote!(<#flatten as #krate::hlist::Concat<#canon>>::Output)
+                }
+            };
+        }
+
+        tails.reverse();
+        (canon, tails)
+    }
+
+    /// the pattern to destructure the fields, `{ x: __typsy_x, skipped: _ }`
+    fn pattern(&self) -> TokenStream {
+        let fields = self.fields.iter().map(|Field { member, binding, kind, .. }| match kind {
+            Kind::Skip { .. } => quote!(#member: _),
+            _ => quote!(#member: #binding),
+        });
+
+        quote!({ #(#fields),* })
+    }
+
+    /// the canon built from the bindings of `pattern`
+    fn canon_expr(&self, mode: Mode) -> TokenStream {
+        let krate = &self.krate;
+        let mut canon = quote!(#krate::hlist::Nil);
+
+        for Field { binding, kind, .. } in self.fields.iter().rev() {
+            canon = match kind {
+                Kind::Field { name: Some(_) } => quote!(#krate::hlist::Cons {
+                    value: #krate::anon::Named::new(#binding),
+                    rest: #canon,
+                }),
+                Kind::Field { name: None } => quote!(#krate::hlist::Cons {
+                    value: #krate::anon::Unnamed(#binding),
+                    rest: #canon,
+                }),
+                Kind::Skip { .. } => canon,
+                Kind::Flatten => {
+                    let flatten = mode.into_flatten(krate, binding);
+                    quote!(#krate::hlist::Concat::concat(#flatten, #canon))
+                }
+            };
+        }
+
+        canon
+    }
+
+    /// destructures the canon in `canon`, and builds `path` from it
+    fn build_from_canon(&self, path: TokenStream) -> TokenStream {
+        let krate = &self.krate;
+        let (_, tails) = self.canon(Mode::Owned);
+
+        let fields = self.fields.iter().zip(tails).map(|(Field { binding, ty, kind, .. }, tail)| match kind {
+            Kind::Field { .. } => quote!(
+                let #krate::hlist::Cons { value: #binding, rest: canon } = canon;
+                let #binding = #binding.0;
+            ),
+            Kind::Skip { default } => quote!(let #binding = #default;),
+            Kind::Flatten => quote!(
+                let (#binding, canon) =
+                    <<#ty as #krate::anon::Transform>::Canon as #krate::hlist::Concat<#tail>>::unconcat(canon);
+                let #binding = <#ty as #krate::anon::Transform>::from_canon(#binding);
+            ),
+        });
+
+        let members = self.fields.iter().map(|field| &field.member);
+        let bindings = self.fields.iter().map(|field| &field.binding);
+
+        quote!({
+            #(#fields)*
+            let #krate::hlist::Nil = canon;
+            #path { #(#members: #bindings),* }
+        })
+    }
+}
+
+impl Options {
+    /// the `Transform` impl, or with `validate` only a `TryTransform` impl, so that the type is only built through it
+    fn impl_transform(
+        &self,
+        ident: &syn::Ident,
+        generics: &syn::Generics,
+        canon: TokenStream,
+        into_canon: TokenStream,
+        build: TokenStream,
+    ) -> TokenStream {
+        let krate = &self.krate;
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+        match (&self.validate, &self.error) {
+            (Some(validate), Some(error)) => quote!(
+                impl #impl_generics #krate::anon::TryTransform for #ident #type_generics #where_clause {
+                    type Canon = #canon;
+                    type Error = #error;
+
+                    fn try_from_canon(
+                        canon: <Self as #krate::anon::TryTransform>::Canon,
+                    ) -> #krate::core::result::Result<Self, Self::Error> {
+                        #validate(#build)
+                    }
+                }
+            ),
+            _ => quote!(
+                impl #impl_generics #krate::anon::Transform for #ident #type_generics #where_clause {
+                    type Canon = #canon;
+
+                    fn from_canon(canon: <Self as #krate::anon::Transform>::Canon) -> Self { #build }
+
+                    fn into_canon(self) -> <Self as #krate::anon::Transform>::Canon { #into_canon }
+                }
+            ),
+        }
+    }
+}
+
+pub fn derive(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+    let syn::DeriveInput {
+        attrs,
+        data,
+        generics,
+        ident,
+        ..
+    } = input;
+
+    let options = Options::new(&attrs)?;
+    let krate = &options.krate;
+
+    let (_, type_generics, where_clause) = generics.split_for_impl();
+
+    // one impl covers every leaf mode `__TypsyM`, the canon decides how its leaves transform
+    let mut deep_generics = generics.clone();
+    {
+        deep_generics.params.push(syn::parse_quote! { __TypsyL });
+        deep_generics.params.push(syn::parse_quote! { __TypsyN });
+        deep_generics.params.push(syn::parse_quote! { __TypsyM });
+        deep_generics.make_where_clause().predicates.push(syn::parse_quote! {
+            <Self as #krate::anon::Transform>::Canon: #krate::anon::DeepTransformFrom<__TypsyL, __TypsyN, __TypsyM>
+        });
+    }
+
+    let (deep_impl_generics, _, deep_where_clause) = deep_generics.split_for_impl();
+
+    let mut try_deep_generics = generics.clone();
+    {
+        try_deep_generics.params.push(
+            syn::parse_quote! { __TypsyL: #krate::anon::TryDeepTransform<<Self as #krate::anon::TryTransform>::Canon, __TypsyN> },
+        );
+        try_deep_generics.params.push(syn::parse_quote! { __TypsyN });
+    }
+
+    let (try_deep_impl_generics, _, _) = try_deep_generics.split_for_impl();
+
+    let mut output = match data {
+        syn::Data::Struct(data) => derive_struct(&options, &ident, &generics, data.fields)?,
+        syn::Data::Enum(data) => derive_enum(&options, &ident, &generics, data.variants)?,
+        syn::Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "You cannot transform a `union`",
+            ))
+        }
+    };
+
+    // like `Transform`, the infallible deep conversions are left out with `validate`
+    if options.validate.is_none() {
+        output.extend(quote! {
+            impl #deep_impl_generics #krate::anon::DeepTransformFrom<__TypsyL, __TypsyN, __TypsyM> for #ident #type_generics #deep_where_clause {
+                fn deep_transform_from(value: __TypsyL) -> Self {
+                    <Self as #krate::anon::Transform>::from_canon(
+                        <<Self as #krate::anon::Transform>::Canon as #krate::anon::DeepTransformFrom<__TypsyL, __TypsyN, __TypsyM>>::deep_transform_from(value),
+                    )
+                }
+            }
+        });
+    }
+
+    let canon_error = quote!(<__TypsyL as #krate::anon::TryDeepTransform<<Self as #krate::anon::TryTransform>::Canon, __TypsyN>>::Error);
+
+    // only `validate` adds a layer for its own error, the fields' errors are otherwise passed through
+    let (error, map_canon_error, try_from_canon) = match options.validate {
+        Some(_) => (
+            quote!(#krate::coprod::CoCons<<Self as #krate::anon::TryTransform>::Error, #canon_error>),
+            quote!(.map_err(#krate::coprod::CoCons::Rest)),
+            quote!(<Self as #krate::anon::TryTransform>::try_from_canon(canon).map_err(#krate::coprod::CoCons::Value)),
+        ),
+        None => (
+            canon_error,
+            quote!(),
+            quote!(#krate::core::result::Result::Ok(<Self as #krate::anon::Transform>::from_canon(canon))),
+        ),
+    };
+
+    output.extend(quote! {
+        impl #try_deep_impl_generics #krate::anon::TryDeepTransformFrom<__TypsyL, __TypsyN> for #ident #type_generics #where_clause {
+            type Error = #error;
+
+            fn try_deep_transform_from(value: __TypsyL) -> #krate::core::result::Result<Self, Self::Error> {
+                let canon = #krate::anon::TryDeepTransform::try_deep_transform(value)#map_canon_error?;
+                #try_from_canon
+            }
+        }
+    });
+
+    Ok(output)
+}
+
+fn derive_struct(
+    options: &Options,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    fields: syn::Fields,
+) -> syn::Result<TokenStream> {
+    let (_, type_generics, where_clause) = generics.split_for_impl();
+    let krate = &options.krate;
+
+    let record = Record::new(&fields, &options.krate)?;
+    // skipped fields never reach the canon, so they may stay private
+    options.check_fields(
+        fields
+            .iter()
+            .zip(&record.fields)
+            .filter(|(_, field)| !matches!(field.kind, Kind::Skip { .. }))
+            .map(|(field, _)| field),
+    )?;
+
+    let mut take_fields_generics = generics.clone();
+    {
+        take_fields_generics.params.push(syn::parse_quote! { __TypsyFieldName });
+        take_fields_generics.params.push(syn::parse_quote! { __TypsyN });
+        let where_clause =
+            quote! { <Self as #krate::anon::TryTransform>::Canon: #krate::anon::RemoveField<__TypsyFieldName, __TypsyN> };
+        match take_fields_generics.where_clause {
+            Some(ref mut wc) => wc.predicates.push(syn::parse_quote!(#where_clause)),
+            None => take_fields_generics.where_clause = Some(syn::parse_quote!(where #where_clause)),
+        }
+    }
+
+    let (take_fields_impl_generics, _, take_fields_where_clause) = take_fields_generics.split_for_impl();
+
+    // `AsRef` borrows each field for `'__typsy`, so every generic parameter must outlive it
+    let mut as_ref_generics = generics.clone();
+    {
+        for param in as_ref_generics.params.iter_mut() {
+            match param {
+                syn::GenericParam::Type(param) => param.bounds.push(syn::parse_quote!('__typsy)),
+                syn::GenericParam::Lifetime(param) => param.bounds.push(syn::parse_quote!('__typsy)),
+                syn::GenericParam::Const(_) => (),
+            }
+        }
+        as_ref_generics.params.insert(0, syn::parse_quote!('__typsy));
+    }
+
+    let (as_ref_impl_generics, _, _) = as_ref_generics.split_for_impl();
+
+    let mut take_ref_fields_generics = as_ref_generics.clone();
+    {
+        // this impl is for a reference, so `Self` in the where clause must name the type itself
+        if let Some(ref mut where_clause) = take_ref_fields_generics.where_clause {
+            *where_clause = syn::parse2(replace_self(quote!(#where_clause), &quote!(#ident #type_generics)))?;
+        }
+        take_ref_fields_generics.params.push(syn::parse_quote! { __TypsyFieldName });
+        take_ref_fields_generics.params.push(syn::parse_quote! { __TypsyN });
+        let where_clause = quote! {
+            <#ident #type_generics as #krate::as_ref::AsRef<'__typsy>>::Ref: #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>
+        };
+        match take_ref_fields_generics.where_clause {
+            Some(ref mut wc) => wc.predicates.push(syn::parse_quote!(#where_clause)),
+            None => take_ref_fields_generics.where_clause = Some(syn::parse_quote!(where #where_clause)),
+        }
+    }
+
+    let (take_ref_fields_impl_generics, _, take_ref_fields_where_clause) = take_ref_fields_generics.split_for_impl();
+
+    let (canon, _) = record.canon(Mode::Owned);
+    let (canon_ref, _) = record.canon(Mode::Ref);
+    let (canon_mut, _) = record.canon(Mode::Mut);
+    let into_canon_ref = record.canon_expr(Mode::Ref);
+    let into_canon_mut = record.canon_expr(Mode::Mut);
+    let pattern = record.pattern();
+    let into_canon = record.canon_expr(Mode::Owned);
+    let build = record.build_from_canon(quote!(Self));
+    let transform = options.impl_transform(
+        ident,
+        generics,
+        canon,
+        quote!(let Self #pattern = self; #into_canon),
+        build,
+    );
+
+    let note = if options.allow_private {
+        quote!(#[doc = "Private fields are exposed through the canon, `#[typsy(allow_private)]`"])
+    } else {
+        quote!()
+    };
+
+    Ok(quote!(
+        #note
+        #transform
+
+        impl #take_fields_impl_generics #krate::anon::RemoveField<__TypsyFieldName, __TypsyN> for #ident #type_generics #take_fields_where_clause {
+            type Value = <<Self as #krate::anon::TryTransform>::Canon as #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>>::Value;
+            type Remainder = <<Self as #krate::anon::TryTransform>::Canon as #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>>::Remainder;
+
+            fn remove_field(self) -> (Self::Value, Self::Remainder) {
+                let Self #pattern = self;
+                #krate::anon::RemoveField::remove_field(#into_canon)
+            }
+        }
+
+        impl #as_ref_impl_generics #krate::as_ref::AsRef<'__typsy> for #ident #type_generics #where_clause {
+            type Ref = #canon_ref;
+            type RefMut = #canon_mut;
+
+            fn as_ref(&'__typsy self) -> Self::Ref {
+                let Self #pattern = self;
+                #into_canon_ref
+            }
+
+            fn as_mut(&'__typsy mut self) -> Self::RefMut {
+                let Self #pattern = self;
+                #into_canon_mut
+            }
+        }
+
+        impl #take_ref_fields_impl_generics #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>
+            for &'__typsy #ident #type_generics #take_ref_fields_where_clause
+        {
+            type Value = <<#ident #type_generics as #krate::as_ref::AsRef<'__typsy>>::Ref as #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>>::Value;
+            type Remainder = <<#ident #type_generics as #krate::as_ref::AsRef<'__typsy>>::Ref as #krate::anon::RemoveField<__TypsyFieldName, __TypsyN>>::Remainder;
+
+            fn remove_field(self) -> (Self::Value, Self::Remainder) {
+                #krate::anon::RemoveField::remove_field(#krate::as_ref::AsRef::as_ref(self))
+            }
+        }
+    ))
+}
+
+/// enums map to a coproduct of anon records, each named after its variant
+fn derive_enum(
+    options: &Options,
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    variants: impl IntoIterator<Item = syn::Variant>,
+) -> syn::Result<TokenStream> {
+    let krate = &options.krate;
+
+    let mut canon = Vec::new();
+    let mut from_canon = Vec::new();
+    let mut into_canon = Vec::new();
+    let mut position = quote!(canon);
+
+    for variant in variants {
+        let name = &variant.ident;
+        let record = Record::new(&variant.fields, &options.krate)?;
+        let (record_canon, _) = record.canon(Mode::Owned);
+        let pattern = record.pattern();
+        let record_into_canon = record.canon_expr(Mode::Owned);
+
+        canon.push(quote!(#krate::anon::Named<#record_canon, #krate::field!(#name)>));
+
+        let build = record.build_from_canon(quote!(Self::#name));
+        let mut variant_pattern = quote!(#krate::coprod::CoCons::Value(canon));
+        for _ in &from_canon {
+            variant_pattern = quote!(#krate::coprod::CoCons::Rest(#variant_pattern));
+        }
+        from_canon.push(quote!(#variant_pattern => {
+            let canon = canon.0;
+            #build
+        }));
+
+        into_canon.push(quote!(Self::#name #pattern => {
+            let canon = #krate::coprod::CoCons::Value(#krate::anon::Named::new(#record_into_canon));
+            #position
+        }));
+
+        position = quote!(#krate::coprod::CoCons::Rest(#position));
+    }
+
+    let mut unreachable = quote!(canon);
+    for _ in &canon {
+        unreachable = quote!(#krate::coprod::CoCons::Rest(#unreachable));
+    }
+
+    // an empty enum's canon is `CoNil` itself, matching on it directly avoids an unreachable arm
+    let build = if from_canon.is_empty() {
+        quote!(match canon {})
+    } else {
+        quote!(match canon {
+            #(#from_canon)*
+            #unreachable => match canon {},
+        })
+    };
+    Ok(options.impl_transform(
+        ident,
+        generics,
+        quote!(#krate::CoProd!(#(#canon),*)),
+        quote!(match self { #(#into_canon)* }),
+        build,
+    ))
+}
+
+/// replaces every `Self` in `tokens` with `with`
+fn replace_self(tokens: TokenStream, with: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            proc_macro2::TokenTree::Ident(ref ident) if ident == "Self" => with.clone(),
+            proc_macro2::TokenTree::Group(group) => {
+                let mut replaced = proc_macro2::Group::new(group.delimiter(), replace_self(group.stream(), with));
+                replaced.set_span(group.span());
+                quote!(#replaced)
+            }
+            token => quote!(#token),
+        })
+        .collect()
+}